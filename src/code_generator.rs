@@ -1,71 +1,566 @@
-use std::collections::HashMap;
+use std::fmt;
+use std::io;
+use std::slice;
 
+use crate::error::{JsonError, Misuse};
+use crate::map;
+use crate::number::Number;
+use crate::position::Position;
 use crate::value::Json;
+use crate::Result;
 
+/// 格式化输出时的排版配置.
+#[derive(Debug, Clone)]
+pub struct PrettyConfig {
+    indent: String,
+    newline: String,
+    space_after_colon: bool,
+    trailing_newline: bool,
+    inline_empty: bool,
+    trailing_commas: bool,
+}
+
+impl Default for PrettyConfig {
+    fn default() -> Self {
+        Self {
+            indent: String::from("  "),
+            newline: String::from("\n"),
+            space_after_colon: true,
+            trailing_newline: false,
+            inline_empty: true,
+            trailing_commas: false,
+        }
+    }
+}
+
+impl PrettyConfig {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 每一层缩进所使用的字符串.
+    pub fn indent(mut self, indent: &str) -> Self {
+        self.indent = indent.to_owned();
+        self
+    }
+
+    /// 每一层缩进`width`个空格.
+    pub fn spaces(self, width: usize) -> Self {
+        self.indent(&" ".repeat(width))
+    }
+
+    /// 每一层缩进`width`个制表符.
+    pub fn tabs(self, width: usize) -> Self {
+        self.indent(&"\t".repeat(width))
+    }
+
+    /// 换行所使用的字符串, 例如`"\n"`或者`"\r\n"`.
+    pub fn newline(mut self, newline: &str) -> Self {
+        self.newline = newline.to_owned();
+        self
+    }
+
+    /// 是否在键值对的冒号后面加一个空格.
+    pub fn space_after_colon(mut self, space_after_colon: bool) -> Self {
+        self.space_after_colon = space_after_colon;
+        self
+    }
+
+    /// 是否在输出的末尾加一个换行.
+    pub fn trailing_newline(mut self, trailing_newline: bool) -> Self {
+        self.trailing_newline = trailing_newline;
+        self
+    }
+
+    /// 是否把空数组和空对象写成`[]`和`{}`, 而不是把括号分成两行.
+    pub fn inline_empty(mut self, inline_empty: bool) -> Self {
+        self.inline_empty = inline_empty;
+        self
+    }
+
+    /// 是否在数组和对象的最后一个元素后面也加上逗号, 只在JSON5模式下有效.
+    pub fn trailing_commas(mut self, trailing_commas: bool) -> Self {
+        self.trailing_commas = trailing_commas;
+        self
+    }
+}
+
+/// 遇到`NaN`和正负无穷这些JSON无法表示的数字时的处理方式.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NonFinite {
+    /// 报告`JsonError::NonFiniteNumber`错误.
+    Error,
+    /// 写成`null`, 与JavaScript的`JSON.stringify`相同.
+    #[default]
+    Null,
+    /// 像JSON5那样写成`NaN`, `Infinity`和`-Infinity`, 输出将不再是合法的JSON.
+    Literal,
+}
+
+/// 控制`CodeGenerator`输出的选项.
+#[derive(Debug, Clone, Default)]
+pub struct GeneratorOptions {
+    ascii_only: bool,
+    html_safe: bool,
+    json5: bool,
+    non_finite: NonFinite,
+    pretty: Option<PrettyConfig>,
+}
+
+impl GeneratorOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 把所有非ASCII字符写成`\uXXXX`, BMP以外的字符写成UTF-16代理对.
+    pub fn ascii_only(mut self, ascii_only: bool) -> Self {
+        self.ascii_only = ascii_only;
+        self
+    }
+
+    /// 转义`<`, `>`, `&`以及U+2028, U+2029, 使输出可以安全地嵌入到HTML的`<script>`中.
+    pub fn html_safe(mut self, html_safe: bool) -> Self {
+        self.html_safe = html_safe;
+        self
+    }
+
+    /// 输出便于手工编辑的JSON5: 由ASCII字母, 数字, `_`和`$`组成的标识符作为键时不加引号,
+    /// 字符串使用需要转义更少的那种引号, `NaN`和`Infinity`原样写出.
+    pub fn json5(mut self, json5: bool) -> Self {
+        self.json5 = json5;
+        self
+    }
+
+    /// 遇到`NaN`和正负无穷时的处理方式, 默认是`NonFinite::Null`. JSON5模式下它们总是原样写出.
+    pub fn non_finite(mut self, non_finite: NonFinite) -> Self {
+        self.non_finite = non_finite;
+        self
+    }
+
+    /// 按照给定的排版配置进行格式化输出, 默认输出是紧凑的.
+    pub fn pretty(mut self, config: PrettyConfig) -> Self {
+        self.pretty = Some(config);
+        self
+    }
+}
+
+/// 把`Json`写成文本. 默认写进一个`String`, 也可以用`from_writer`直接写进任意的`fmt::Write`.
 #[derive(Default)]
-pub struct CodeGenerator {
-    value: String,
+pub struct CodeGenerator<W = String> {
+    writer: JsonWriter<W>,
 }
 
 impl CodeGenerator {
     pub fn new() -> Self {
+        Self::with_options(GeneratorOptions::default())
+    }
+
+    pub fn with_options(options: GeneratorOptions) -> Self {
+        Self::from_writer(String::new(), options)
+    }
+
+    pub fn product(self) -> String {
+        self.writer.into_inner()
+    }
+}
+
+impl<W: fmt::Write> CodeGenerator<W> {
+    /// 直接写进`out`, 不经过中间的`String`.
+    pub fn from_writer(out: W, options: GeneratorOptions) -> Self {
         Self {
-            value: String::new(),
+            writer: JsonWriter::from_writer(out, options),
         }
     }
 
-    pub fn gather(&mut self, json: &Json) {
-        self.write_json(json)
+    /// 在`NonFinite::Error`策略下遇到`NaN`或者正负无穷, 或者写入失败时返回错误.
+    pub fn gather(&mut self, json: &Json) -> Result<()> {
+        self.writer.restart();
+        self.writer.value(json)
     }
 
-    pub fn product(self) -> String {
-        self.value
+    pub fn into_inner(self) -> W {
+        self.writer.into_inner()
+    }
+}
+
+/// 流式的JSON写入器, 不需要先构建出`Json`, 适合逐条写出数据库游标这样的大量数据.
+///
+/// 逗号, 缩进和字符串的转义都由它负责. 调用的顺序不正确时, 例如在数组中写键, 或者`end`多于`begin_*`,
+/// 会返回`JsonError::InvalidWrite`, 这时不会写出任何内容.
+///
+/// ```text
+/// begin_object, key("a"), begin_array, value(1), value(true), end, end
+///
+/// {"a":[1,true]}
+/// ```
+#[derive(Default)]
+pub struct JsonWriter<W = String> {
+    out: W,
+    options: GeneratorOptions,
+    stack: Vec<Container>,
+    // 顶层的值已经写完.
+    done: bool,
+}
+
+// 正在写的数组或者对象.
+struct Container {
+    object: bool,
+    empty: bool,
+    // 对象已经写了键, 正在等待值.
+    key: bool,
+}
+
+impl JsonWriter {
+    pub fn new() -> Self {
+        Self::with_options(GeneratorOptions::default())
+    }
+
+    pub fn with_options(options: GeneratorOptions) -> Self {
+        Self::from_writer(String::new(), options)
+    }
+}
+
+impl<W: fmt::Write> JsonWriter<W> {
+    pub fn from_writer(out: W, options: GeneratorOptions) -> Self {
+        Self {
+            out,
+            options,
+            stack: Vec::new(),
+            done: false,
+        }
     }
 
-    fn write_json(&mut self, json: &Json) {
+    pub fn begin_object(&mut self) -> Result<()> {
+        self.begin('{', true)
+    }
+
+    pub fn begin_array(&mut self) -> Result<()> {
+        self.begin('[', false)
+    }
+
+    /// 写出对象的下一个键.
+    pub fn key(&mut self, key: &str) -> Result<()> {
+        let (empty, waiting) = match self.stack.last() {
+            Some(container) if container.object => (container.empty, container.key),
+            _ => return Err(misuse(Misuse::KeyOutsideObject)),
+        };
+        if waiting {
+            return Err(misuse(Misuse::KeyWithoutValue));
+        }
+
+        if !empty {
+            self.write_char(',')?;
+        }
+        self.write_newline()?;
+        self.write_key(key)?;
+        self.write_colon()?;
+
+        if let Some(container) = self.stack.last_mut() {
+            container.empty = false;
+            container.key = true;
+        }
+        Ok(())
+    }
+
+    /// 写出一个完整的值. 不使用递归, 嵌套再深也不会栈溢出.
+    pub fn value(&mut self, json: &Json) -> Result<()> {
+        let mut stack: Vec<Frame<'_>> = Vec::new();
+        let mut next = Some(json);
+
+        loop {
+            if let Some(json) = next.take() {
+                match *json {
+                    Json::Array(ref a) => {
+                        self.begin_array()?;
+                        stack.push(Frame::Array(a.iter()));
+                    }
+                    Json::Object(ref o) => {
+                        self.begin_object()?;
+                        stack.push(Frame::Object(o.iter()));
+                    }
+                    ref scalar => self.scalar(scalar)?,
+                }
+            }
+
+            let element = match stack.last_mut() {
+                None => return Ok(()),
+                Some(Frame::Array(ref mut iter)) => iter.next().map(|value| (None, value)),
+                Some(Frame::Object(ref mut iter)) => {
+                    iter.next().map(|(key, value)| (Some(key), value))
+                }
+            };
+
+            match element {
+                Some((key, value)) => {
+                    if let Some(key) = key {
+                        self.key(key)?;
+                    }
+                    next = Some(value);
+                }
+                None => {
+                    stack.pop();
+                    self.end()?;
+                }
+            }
+        }
+    }
+
+    /// 结束最内层的数组或者对象.
+    pub fn end(&mut self) -> Result<()> {
+        let (object, empty) = match self.stack.last() {
+            Some(container) if container.key => return Err(misuse(Misuse::KeyWithoutValue)),
+            Some(container) => (container.object, container.empty),
+            None => return Err(misuse(Misuse::UnbalancedEnd)),
+        };
+        self.stack.pop();
+
+        if empty {
+            if let Some(PrettyConfig {
+                inline_empty: false,
+                ..
+            }) = self.options.pretty
+            {
+                self.write_newline()?;
+            }
+        } else {
+            if self.options.json5 {
+                if let Some(PrettyConfig {
+                    trailing_commas: true,
+                    ..
+                }) = self.options.pretty
+                {
+                    self.write_char(',')?;
+                }
+            }
+            self.write_newline()?;
+        }
+
+        self.write_char(if object { '}' } else { ']' })?;
+        self.complete()
+    }
+
+    /// 检查顶层的值已经写完, 然后返回`out`.
+    pub fn finish(self) -> Result<W> {
+        if self.done {
+            Ok(self.out)
+        } else {
+            Err(misuse(Misuse::Unfinished))
+        }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+
+    // `CodeGenerator`每次`gather`都开始一个新的顶层值.
+    pub(crate) fn restart(&mut self) {
+        self.stack.clear();
+        self.done = false;
+    }
+
+    fn begin(&mut self, open: char, object: bool) -> Result<()> {
+        self.before_value()?;
+        self.write_char(open)?;
+        self.stack.push(Container {
+            object,
+            empty: true,
+            key: false,
+        });
+        Ok(())
+    }
+
+    fn scalar(&mut self, json: &Json) -> Result<()> {
+        self.before_value()?;
         match *json {
-            Json::Null => self.write("null"),
-            Json::Boolean(ref b) => self.write(if *b { "true" } else { "false" }),
-            Json::Number(ref n) => self.write(&n.to_string()),
-            Json::String(ref s) => self.write(&format!("{:?}", s)),
-            Json::Array(ref a) => self.write_array(a),
-            Json::Object(ref o) => self.write_object(o),
+            Json::Null => self.write("null")?,
+            Json::Boolean(ref b) => self.write(if *b { "true" } else { "false" })?,
+            Json::Number(ref n) => self.write_number(n)?,
+            Json::String(ref s) => self.write_string(s)?,
+            Json::Array(_) | Json::Object(_) => unreachable!("containers are written by `value`"),
         }
+        self.complete()
     }
 
-    fn write(&mut self, slice: &str) {
-        self.value.push_str(slice);
+    // 先检查值能否出现在这里, 再写出它前面的逗号和换行.
+    fn before_value(&mut self) -> Result<()> {
+        match self.stack.last() {
+            None if self.done => Err(misuse(Misuse::MultipleValues)),
+            None => Ok(()),
+            Some(container) if container.object && !container.key => {
+                Err(misuse(Misuse::ValueWithoutKey))
+            }
+            Some(container) if container.object => Ok(()),
+            Some(container) => {
+                if !container.empty {
+                    self.write_char(',')?;
+                }
+                self.write_newline()?;
+                if let Some(container) = self.stack.last_mut() {
+                    container.empty = false;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    // 一个值写完了.
+    fn complete(&mut self) -> Result<()> {
+        match self.stack.last_mut() {
+            Some(container) => container.key = false,
+            None => {
+                self.done = true;
+                if let Some(PrettyConfig {
+                    trailing_newline: true,
+                    ref newline,
+                    ..
+                }) = self.options.pretty
+                {
+                    self.out.write_str(newline).map_err(write_error)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn write(&mut self, slice: &str) -> Result<()> {
+        self.out.write_str(slice).map_err(write_error)
     }
 
-    fn write_char(&mut self, ch: char) {
-        self.value.push(ch);
+    fn write_char(&mut self, ch: char) -> Result<()> {
+        self.out.write_char(ch).map_err(write_error)
     }
 
-    fn write_array(&mut self, array: &[Json]) {
-        self.write_char('[');
+    // 保留了原始文本的数字总是原样写出, 即使它超出了`f64`的范围.
+    fn write_number(&mut self, n: &Number) -> Result<()> {
+        let f = match n.as_f64() {
+            Some(f) if n.as_str().is_none() && !f.is_finite() => f,
+            _ => return write!(self.out, "{}", n).map_err(write_error),
+        };
+
+        let policy = if self.options.json5 {
+            NonFinite::Literal
+        } else {
+            self.options.non_finite
+        };
+
+        match policy {
+            NonFinite::Error => Err(JsonError::NonFiniteNumber { value: f }),
+            NonFinite::Null => self.write("null"),
+            NonFinite::Literal if f.is_nan() => self.write("NaN"),
+            NonFinite::Literal if f > 0.0 => self.write("Infinity"),
+            NonFinite::Literal => self.write("-Infinity"),
+        }
+    }
+
+    // JSON5模式下, ASCII标识符形式的键不加引号.
+    // `NaN`和`Infinity`虽然也是合法的标识符, 但是为了不和数字混淆, 仍然加上引号.
+    fn write_key(&mut self, key: &str) -> Result<()> {
+        if self.options.json5 && is_identifier(key) && key != "NaN" && key != "Infinity" {
+            self.write(key)
+        } else {
+            self.write_string(key)
+        }
+    }
+
+    // JSON5模式下, 字符串中双引号比单引号多时使用单引号, 这样需要转义的引号更少.
+    // 不需要转义的部分整段写出.
+    fn write_string(&mut self, s: &str) -> Result<()> {
+        let quote = if self.options.json5 && s.matches('"').count() > s.matches('\'').count() {
+            '\''
+        } else {
+            '"'
+        };
+
+        self.write_char(quote)?;
 
-        for (i, elem) in array.iter().enumerate() {
-            self.write_json(elem);
-            if i != (array.len() - 1) {
-                self.write_char(',');
+        let mut plain = 0;
+        for (index, ch) in s.char_indices() {
+            let escape = match ch {
+                _ if ch == quote => Some(if quote == '"' { "\\\"" } else { "\\'" }),
+                '\\' => Some("\\\\"),
+                '\u{8}' => Some("\\b"),
+                '\u{c}' => Some("\\f"),
+                '\n' => Some("\\n"),
+                '\r' => Some("\\r"),
+                '\t' => Some("\\t"),
+                '\u{0}'..='\u{1f}' => None,
+                '<' | '>' | '&' | '\u{2028}' | '\u{2029}' if self.options.html_safe => None,
+                _ if !ch.is_ascii() && self.options.ascii_only => None,
+                _ => continue,
+            };
+
+            self.write(&s[plain..index])?;
+            plain = index + ch.len_utf8();
+            match escape {
+                Some(escape) => self.write(escape)?,
+                None => self.write_unicode_escape(ch)?,
             }
         }
 
-        self.write_char(']');
+        self.write(&s[plain..])?;
+        self.write_char(quote)
     }
 
-    fn write_object(&mut self, object: &HashMap<String, Json>) {
-        self.write_char('{');
+    // BMP以外的字符会被编码成两个UTF-16代码单元, 也就是一对代理.
+    fn write_unicode_escape(&mut self, ch: char) -> Result<()> {
+        let mut buf = [0; 2];
+        for unit in ch.encode_utf16(&mut buf) {
+            write!(self.out, "\\u{:04x}", unit).map_err(write_error)?;
+        }
+        Ok(())
+    }
 
-        for (i, (key, value)) in object.iter().enumerate() {
-            self.write(&format!("{:?}", key));
-            self.write_char(':');
-            self.write_json(value);
-            if i != (object.len() - 1) {
-                self.write_char(',');
+    // 紧凑输出时什么都不写.
+    fn write_newline(&mut self) -> Result<()> {
+        if let Some(ref config) = self.options.pretty {
+            self.out.write_str(&config.newline).map_err(write_error)?;
+            for _ in 0..self.stack.len() {
+                self.out.write_str(&config.indent).map_err(write_error)?;
             }
         }
+        Ok(())
+    }
 
-        self.write_char('}');
+    fn write_colon(&mut self) -> Result<()> {
+        self.write_char(':')?;
+        if let Some(PrettyConfig {
+            space_after_colon: true,
+            ..
+        }) = self.options.pretty
+        {
+            self.write_char(' ')?;
+        }
+        Ok(())
     }
 }
+
+fn misuse(misuse: Misuse) -> JsonError {
+    JsonError::InvalidWrite { misuse }
+}
+
+// `fmt::Error`不带任何信息, `io::Write`的错误由`to_writer`另外保存.
+fn write_error(_: fmt::Error) -> JsonError {
+    JsonError::Io {
+        error: io::Error::other("failed to write the output"),
+        position: Position::start(),
+    }
+}
+
+// 只输出ASCII的标识符. `Tokenizer`接受的范围更宽, 其中有些字符(例如`½`)不是ECMAScript的标识符,
+// 其他JSON5解析器无法读取, 所以非ASCII的键一律加上引号.
+fn is_identifier(key: &str) -> bool {
+    let mut chars = key.chars();
+
+    match chars.next() {
+        Some(ch) if ch == '_' || ch == '$' || ch.is_ascii_alphabetic() => {}
+        _ => return false,
+    }
+
+    chars.all(|ch| ch == '_' || ch == '$' || ch.is_ascii_alphanumeric())
+}
+
+// 正在输出的数组或者对象.
+enum Frame<'a> {
+    Array(slice::Iter<'a, Json>),
+    Object(map::Iter<'a>),
+}
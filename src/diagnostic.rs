@@ -0,0 +1,334 @@
+use std::fmt::Write;
+
+use crate::error::JsonError;
+use crate::position::{Position, Span};
+use crate::token::Token;
+use crate::tokenizer::is_whitespace;
+
+/// 一条面向用户的诊断信息: 出错的位置, 一句简短的说明, 一个标注在出错位置下方的标签, 以及可能的修改建议.
+///
+/// ```text
+/// error: trailing comma not allowed
+///  --> line 3, column 9
+///   |
+/// 3 |   "b": 2,
+///   |         ^ remove this comma
+///   |
+///   = hint: JSON does not allow a comma after the last element
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub message: String,
+    pub label: String,
+    pub hint: Option<String>,
+    pub position: Position,
+    pub width: usize,
+}
+
+impl Diagnostic {
+    pub fn new(error: &JsonError) -> Self {
+        let position = error.position();
+        let (message, label, hint, width) = match *error {
+            JsonError::UnexpectedToken { ref token, span } => (
+                format!("unexpected token {}", token),
+                String::from("unexpected token"),
+                token_hint(token),
+                span_width(span),
+            ),
+            JsonError::UnexpectedCharacter { ch, .. } => (
+                format!("unexpected character {:?}", ch),
+                String::from("not valid in JSON"),
+                character_hint(ch),
+                1,
+            ),
+            JsonError::UnexpectedEof { .. } => (
+                String::from("unexpected end of input"),
+                String::from("expected more input here"),
+                Some(String::from(
+                    "the document ends before a value or a closing bracket",
+                )),
+                1,
+            ),
+            JsonError::InvalidLiteral { ref literal, .. } => (
+                format!("invalid literal `{}`", literal),
+                String::from("not a JSON literal"),
+                Some(literal_hint(literal)),
+                literal.chars().count(),
+            ),
+            JsonError::InvalidNumber { ref number, .. } => (
+                format!("invalid number `{}`", number),
+                String::from("invalid number"),
+                Some(String::from(number_hint(number))),
+                number.chars().count(),
+            ),
+            JsonError::UnterminatedString { .. } => (
+                String::from("unterminated string"),
+                String::from("string starts here"),
+                Some(String::from("add a closing `\"`")),
+                1,
+            ),
+            JsonError::UnterminatedComment { .. } => (
+                String::from("unterminated block comment"),
+                String::from("comment starts here"),
+                Some(String::from("add a closing `*/`")),
+                2,
+            ),
+            JsonError::InvalidUtf8 { .. } => (
+                String::from("invalid UTF-8 in input"),
+                String::from("invalid byte sequence"),
+                Some(String::from("JSON text must be encoded as UTF-8")),
+                1,
+            ),
+            JsonError::Io { ref error, .. } => (
+                format!("I/O error: {}", error),
+                String::from("I/O failed here"),
+                None,
+                1,
+            ),
+            JsonError::InvalidEscape { ch, .. } => (
+                format!("invalid escape `\\{}`", ch),
+                String::from("unknown escape"),
+                Some(String::from(
+                    r#"valid escapes are \" \\ \/ \b \f \n \r \t and \uXXXX"#,
+                )),
+                1 + ch.len_utf8(),
+            ),
+            JsonError::InvalidUnicodeEscape { .. } => (
+                String::from("invalid unicode escape"),
+                String::from("invalid escape"),
+                Some(String::from("`\\u` must be followed by four hex digits")),
+                2,
+            ),
+            JsonError::LoneSurrogate { .. } => (
+                String::from("lone surrogate in unicode escape"),
+                String::from("unpaired surrogate"),
+                Some(String::from(
+                    r"a high surrogate must be followed by a low surrogate, e.g. \uD83D\uDE00",
+                )),
+                6,
+            ),
+            JsonError::ControlCharacter { ch, .. } => (
+                format!("control character U+{:04X} in string", ch as u32),
+                String::from("must be escaped"),
+                Some(control_character_hint(ch)),
+                1,
+            ),
+            JsonError::TrailingCharacters { .. } => (
+                String::from("trailing characters after the JSON value"),
+                String::from("unexpected content"),
+                Some(String::from(
+                    "a JSON document contains exactly one top-level value",
+                )),
+                1,
+            ),
+            JsonError::TrailingComma { .. } => (
+                String::from("trailing comma not allowed"),
+                String::from("remove this comma"),
+                Some(String::from(
+                    "JSON does not allow a comma after the last element",
+                )),
+                1,
+            ),
+            JsonError::MissingComma { .. } => (
+                String::from("missing comma between elements"),
+                String::from("expected `,` before this"),
+                Some(String::from("insert a `,` to separate the elements")),
+                1,
+            ),
+            JsonError::LimitExceeded { limit, .. } => (
+                format!("{} limit exceeded", limit),
+                String::from("limit exceeded here"),
+                Some(String::from(
+                    "the limit can be raised through `ParserOptions` for trusted input",
+                )),
+                1,
+            ),
+            JsonError::NonFiniteNumber { value } => (
+                format!("cannot represent {} as a JSON number", value),
+                String::from("not representable"),
+                Some(String::from(
+                    "choose another `NonFinite` policy in `GeneratorOptions`",
+                )),
+                1,
+            ),
+            JsonError::InvalidWrite { misuse } => (
+                format!("invalid write: {}", misuse),
+                String::from("invalid write"),
+                Some(String::from(
+                    "every `begin_*` needs a matching `end`, and keys only belong in objects",
+                )),
+                1,
+            ),
+            JsonError::DuplicateKey {
+                ref key,
+                first,
+                second,
+            } => (
+                format!("duplicate key {:?}", key),
+                String::from("duplicate key"),
+                Some(format!(
+                    "the key was first defined at line {}, column {}",
+                    first.start.line, first.start.column
+                )),
+                span_width(second),
+            ),
+        };
+
+        Self {
+            message,
+            label,
+            hint,
+            position,
+            width: width.max(1),
+        }
+    }
+
+    /// 把诊断信息连同出错的那一行源码一起渲染出来.
+    pub fn render(&self, source: &str) -> String {
+        let line_number = self.position.line.to_string();
+        let gutter = " ".repeat(line_number.len());
+        let line = source
+            .split('\n')
+            .nth(self.position.line - 1)
+            .unwrap_or("")
+            .trim_end_matches('\r');
+
+        // 制表符原样保留, 使得插入符号和源码能够对齐.
+        let padding: String = line
+            .chars()
+            .chain(std::iter::repeat(' '))
+            .take(self.position.column - 1)
+            .map(|ch| if ch == '\t' { '\t' } else { ' ' })
+            .collect();
+
+        let mut out = String::new();
+        let _ = writeln!(out, "error: {}", self.message);
+        let _ = writeln!(
+            out,
+            "{}--> line {}, column {}",
+            gutter, self.position.line, self.position.column
+        );
+        let _ = writeln!(out, "{} |", gutter);
+        let _ = writeln!(out, "{} | {}", line_number, line);
+        let _ = writeln!(
+            out,
+            "{} | {}{} {}",
+            gutter,
+            padding,
+            "^".repeat(self.width),
+            self.label
+        );
+        if let Some(ref hint) = self.hint {
+            let _ = writeln!(out, "{} |", gutter);
+            let _ = writeln!(out, "{} = hint: {}", gutter, hint);
+        }
+
+        out
+    }
+}
+
+/// 渲染`error`在`source`中的诊断信息.
+pub fn render(source: &str, error: &JsonError) -> String {
+    Diagnostic::new(error).render(source)
+}
+
+// 跨行的span只标注它的第一个字符.
+fn span_width(span: Span) -> usize {
+    if span.start.line == span.end.line {
+        span.end.column - span.start.column
+    } else {
+        1
+    }
+}
+
+fn token_hint(token: &Token) -> Option<String> {
+    match *token {
+        Token::Colon => Some(String::from(
+            "`:` is only allowed between a key and its value",
+        )),
+        Token::Comma => Some(String::from("expected a value before this `,`")),
+        Token::BracketOff | Token::BraceOff => Some(String::from(
+            "this closing bracket does not match the opening one",
+        )),
+        _ => None,
+    }
+}
+
+fn character_hint(ch: char) -> Option<String> {
+    match ch {
+        '\'' => Some(String::from("JSON strings must use double quotes")),
+        '/' => Some(String::from(
+            "comments are not allowed in JSON, see `ParserOptions::allow_comments`",
+        )),
+        '+' => Some(String::from("numbers cannot start with `+`")),
+        '.' => Some(String::from("numbers must have a digit before the `.`")),
+        _ if is_whitespace(ch, true) => Some(String::from(
+            "only space, tab, `\\n` and `\\r` are whitespace in JSON, see `ParserOptions::allow_extended_whitespace`",
+        )),
+        _ => None,
+    }
+}
+
+fn literal_hint(literal: &str) -> String {
+    let lower = literal.to_ascii_lowercase();
+
+    ["true", "false", "null"]
+        .iter()
+        .find(|&&candidate| {
+            candidate == lower
+                || candidate.starts_with(&lower)
+                || edit_distance(candidate, &lower) <= 2
+        })
+        .map(|candidate| format!("did you mean `{}`?", candidate))
+        .unwrap_or_else(|| {
+            String::from("strings must be quoted, the only literals are `true`, `false` and `null`")
+        })
+}
+
+fn number_hint(number: &str) -> &'static str {
+    let digits = number.trim_start_matches('-');
+
+    if number.parse::<f64>().is_ok_and(f64::is_infinite) {
+        "the number is out of range for f64, see `ParserOptions::arbitrary_precision`"
+    } else if digits.is_empty() {
+        "expected a digit after `-`"
+    } else if digits.len() > 1 && digits.starts_with('0') && !digits[1..].starts_with('.') {
+        "leading zeros are not allowed"
+    } else if number.ends_with('.') {
+        "expected a digit after the decimal point"
+    } else if number.ends_with(['e', 'E', '+', '-']) {
+        "expected a digit in the exponent"
+    } else {
+        "numbers must follow the JSON number grammar"
+    }
+}
+
+fn control_character_hint(ch: char) -> String {
+    match ch {
+        '\n' => String::from("write a newline as `\\n`"),
+        '\r' => String::from("write a carriage return as `\\r`"),
+        '\t' => String::from("write a tab as `\\t`"),
+        _ => format!("write it as `\\u{:04x}`", ch as u32),
+    }
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, &cb) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = if ca == cb {
+                previous
+            } else {
+                1 + previous.min(row[j]).min(row[j + 1])
+            };
+            previous = current;
+        }
+    }
+
+    row[b.len()]
+}
@@ -1,2 +1,316 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use crate::position::{Position, Span};
+use crate::token::Token;
+
+/// `ParserOptions`中可以配置的各种上限.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Limit {
+    Depth,
+    InputSize,
+    StringLength,
+    ElementCount,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Limit::Depth => "nesting depth",
+            Limit::InputSize => "input size",
+            Limit::StringLength => "string length",
+            Limit::ElementCount => "element count",
+        })
+    }
+}
+
+/// `JsonWriter`的调用顺序不正确的各种情形.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Misuse {
+    /// 在数组中或者顶层写了键.
+    KeyOutsideObject,
+    /// 对象中的值前面没有键.
+    ValueWithoutKey,
+    /// 键的后面没有值, 就写了另一个键或者结束了对象.
+    KeyWithoutValue,
+    /// 没有需要结束的数组或者对象.
+    UnbalancedEnd,
+    /// 顶层的值已经写完了.
+    MultipleValues,
+    /// 顶层的值还没有写完.
+    Unfinished,
+}
+
+impl fmt::Display for Misuse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Misuse::KeyOutsideObject => "a key outside of an object",
+            Misuse::ValueWithoutKey => "a value without a key",
+            Misuse::KeyWithoutValue => "a key without a value",
+            Misuse::UnbalancedEnd => "an end without a matching begin",
+            Misuse::MultipleValues => "more than one top-level value",
+            Misuse::Unfinished => "an unfinished top-level value",
+        })
+    }
+}
+
 #[derive(Debug)]
-pub enum JsonError {}
+pub enum JsonError {
+    UnexpectedToken {
+        token: Token,
+        span: Span,
+    },
+    UnexpectedCharacter {
+        ch: char,
+        position: Position,
+    },
+    UnexpectedEof {
+        position: Position,
+    },
+    InvalidLiteral {
+        literal: String,
+        position: Position,
+    },
+    InvalidNumber {
+        number: String,
+        position: Position,
+    },
+    UnterminatedString {
+        position: Position,
+    },
+    UnterminatedComment {
+        position: Position,
+    },
+    InvalidUtf8 {
+        position: Position,
+    },
+    Io {
+        error: io::Error,
+        position: Position,
+    },
+    InvalidEscape {
+        ch: char,
+        position: Position,
+    },
+    InvalidUnicodeEscape {
+        position: Position,
+    },
+    LoneSurrogate {
+        position: Position,
+    },
+    ControlCharacter {
+        ch: char,
+        position: Position,
+    },
+    TrailingCharacters {
+        position: Position,
+    },
+    TrailingComma {
+        position: Position,
+    },
+    MissingComma {
+        position: Position,
+    },
+    LimitExceeded {
+        limit: Limit,
+        position: Position,
+    },
+    NonFiniteNumber {
+        value: f64,
+    },
+    InvalidWrite {
+        misuse: Misuse,
+    },
+    DuplicateKey {
+        key: String,
+        first: Span,
+        second: Span,
+    },
+}
+
+impl JsonError {
+    pub fn position(&self) -> Position {
+        match *self {
+            JsonError::UnexpectedToken { span, .. } => span.start,
+            JsonError::DuplicateKey { second, .. } => second.start,
+            // 生成JSON时的错误与输入无关.
+            JsonError::NonFiniteNumber { .. } | JsonError::InvalidWrite { .. } => Position::start(),
+            JsonError::UnexpectedCharacter { position, .. }
+            | JsonError::UnexpectedEof { position }
+            | JsonError::InvalidLiteral { position, .. }
+            | JsonError::InvalidNumber { position, .. }
+            | JsonError::UnterminatedString { position }
+            | JsonError::UnterminatedComment { position }
+            | JsonError::InvalidUtf8 { position }
+            | JsonError::Io { position, .. }
+            | JsonError::InvalidEscape { position, .. }
+            | JsonError::InvalidUnicodeEscape { position }
+            | JsonError::LoneSurrogate { position }
+            | JsonError::ControlCharacter { position, .. }
+            | JsonError::TrailingCharacters { position }
+            | JsonError::TrailingComma { position }
+            | JsonError::MissingComma { position }
+            | JsonError::LimitExceeded { position, .. } => position,
+        }
+    }
+}
+
+// `io::Error`没有实现`PartialEq`, I/O错误只比较错误的种类.
+impl PartialEq for JsonError {
+    fn eq(&self, other: &Self) -> bool {
+        use self::JsonError::*;
+
+        match (self, other) {
+            (UnexpectedToken { token, span }, UnexpectedToken { token: t, span: s }) => {
+                token == t && span == s
+            }
+            (UnexpectedCharacter { ch, position }, UnexpectedCharacter { ch: c, position: p })
+            | (InvalidEscape { ch, position }, InvalidEscape { ch: c, position: p })
+            | (ControlCharacter { ch, position }, ControlCharacter { ch: c, position: p }) => {
+                ch == c && position == p
+            }
+            (
+                InvalidLiteral {
+                    literal: text,
+                    position,
+                },
+                InvalidLiteral {
+                    literal: t,
+                    position: p,
+                },
+            )
+            | (
+                InvalidNumber {
+                    number: text,
+                    position,
+                },
+                InvalidNumber {
+                    number: t,
+                    position: p,
+                },
+            ) => text == t && position == p,
+            (UnexpectedEof { position }, UnexpectedEof { position: p })
+            | (UnterminatedString { position }, UnterminatedString { position: p })
+            | (UnterminatedComment { position }, UnterminatedComment { position: p })
+            | (InvalidUtf8 { position }, InvalidUtf8 { position: p })
+            | (InvalidUnicodeEscape { position }, InvalidUnicodeEscape { position: p })
+            | (LoneSurrogate { position }, LoneSurrogate { position: p })
+            | (TrailingCharacters { position }, TrailingCharacters { position: p })
+            | (TrailingComma { position }, TrailingComma { position: p })
+            | (MissingComma { position }, MissingComma { position: p }) => position == p,
+            (
+                Io { error, position },
+                Io {
+                    error: e,
+                    position: p,
+                },
+            ) => error.kind() == e.kind() && position == p,
+            (
+                LimitExceeded { limit, position },
+                LimitExceeded {
+                    limit: l,
+                    position: p,
+                },
+            ) => limit == l && position == p,
+            (NonFiniteNumber { value }, NonFiniteNumber { value: v }) => value == v,
+            (InvalidWrite { misuse }, InvalidWrite { misuse: m }) => misuse == m,
+            (
+                DuplicateKey { key, first, second },
+                DuplicateKey {
+                    key: k,
+                    first: f,
+                    second: s,
+                },
+            ) => key == k && first == f && second == s,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for JsonError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            JsonError::UnexpectedToken { ref token, span } => {
+                write!(f, "Unexpected token {} at {}", token, span.start)
+            }
+            JsonError::UnexpectedCharacter { ch, position } => {
+                write!(f, "Unexpected character {:?} at {}", ch, position)
+            }
+            JsonError::UnexpectedEof { position } => {
+                write!(f, "Unexpected end of JSON at {}", position)
+            }
+            JsonError::InvalidLiteral {
+                ref literal,
+                position,
+            } => write!(f, "Invalid literal `{}` at {}", literal, position),
+            JsonError::InvalidNumber {
+                ref number,
+                position,
+            } => write!(f, "Invalid number `{}` at {}", number, position),
+            JsonError::UnterminatedString { position } => {
+                write!(f, "Unterminated string starting at {}", position)
+            }
+            JsonError::UnterminatedComment { position } => {
+                write!(f, "Unterminated block comment starting at {}", position)
+            }
+            JsonError::InvalidUtf8 { position } => {
+                write!(f, "Invalid UTF-8 at {}", position)
+            }
+            JsonError::Io {
+                ref error,
+                position,
+            } => write!(f, "I/O error at {}: {}", position, error),
+            JsonError::InvalidEscape { ch, position } => {
+                write!(f, "Invalid escape `\\{}` at {}", ch, position)
+            }
+            JsonError::InvalidUnicodeEscape { position } => {
+                write!(f, "Invalid unicode escape at {}", position)
+            }
+            JsonError::LoneSurrogate { position } => {
+                write!(f, "Lone surrogate in unicode escape at {}", position)
+            }
+            JsonError::ControlCharacter { ch, position } => write!(
+                f,
+                "Control character U+{:04X} must be escaped at {}",
+                ch as u32, position
+            ),
+            JsonError::TrailingCharacters { position } => {
+                write!(f, "Trailing characters at {}", position)
+            }
+            JsonError::TrailingComma { position } => {
+                write!(f, "Trailing comma at {}", position)
+            }
+            JsonError::MissingComma { position } => {
+                write!(f, "Missing comma at {}", position)
+            }
+            JsonError::LimitExceeded { limit, position } => {
+                write!(f, "Limit on {} exceeded at {}", limit, position)
+            }
+            JsonError::NonFiniteNumber { value } => {
+                write!(f, "Cannot represent {} as a JSON number", value)
+            }
+            JsonError::InvalidWrite { misuse } => {
+                write!(f, "Invalid write: {}", misuse)
+            }
+            JsonError::DuplicateKey {
+                ref key,
+                first,
+                second,
+            } => write!(
+                f,
+                "Duplicate key {:?} at {}, first defined at {}",
+                key, second.start, first.start
+            ),
+        }
+    }
+}
+
+impl Error for JsonError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match *self {
+            JsonError::Io { ref error, .. } => Some(error),
+            _ => None,
+        }
+    }
+}
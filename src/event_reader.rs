@@ -0,0 +1,674 @@
+use std::io::Read;
+
+use crate::error::{JsonError, Limit};
+use crate::parser::ParserOptions;
+use crate::position::Span;
+use crate::push_parser::PushBuffer;
+use crate::token::{SpannedToken, Token};
+use crate::tokenizer::Tokenizer;
+use crate::value::Json;
+use crate::Result;
+
+/// `EventReader`给出的事件.
+///
+/// `Scalar`中的值只会是`Null`, `String`, `Number`或者`Boolean`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    StartObject,
+    Key(String),
+    EndObject,
+    StartArray,
+    EndArray,
+    Scalar(Json),
+}
+
+/// 拉取式的事件解析器.
+///
+/// 每次调用`next_event`都只读入必要的token, 然后给出下一个事件, 不会在内存中构建`Json`,
+/// 所需的内存只与嵌套的深度有关, 适合处理体积很大的输入. 数组和对象的嵌套是否匹配, 逗号和冒号是否正确,
+/// 以及`ParserOptions`中的各种上限都会在这里检查. 顶层的值读完之后, 还会检查后面是否有多余的内容.
+///
+/// ```text
+/// {"a": [1, true]}
+///
+/// StartObject, Key("a"), StartArray, Scalar(1), Scalar(true), EndArray, EndObject
+/// ```
+pub struct EventReader<'a> {
+    source: Source<'a>,
+    options: ParserOptions,
+    span: Span,
+    pending: Option<(Token, Span)>,
+    stack: Vec<Level>,
+    phase: Phase,
+    recovering: bool,
+    errors: Vec<JsonError>,
+    eof: bool,
+    failed: bool,
+    // 推送模式下, 已有的输入不足以给出下一个事件.
+    suspended: bool,
+}
+
+// token的来源: 完整的输入, 或者`PushParser`陆续提供的输入.
+enum Source<'a> {
+    Text(Tokenizer<'a>),
+    Push(PushBuffer),
+}
+
+// 栈上的一层, 也就是一个正在读取中的数组或者对象.
+struct Level {
+    state: State,
+    comma: Span,
+    // 已经开始读取的元素个数, 对于数组来说, 当前元素的下标就是`count - 1`.
+    count: usize,
+    // 对象当前的键.
+    key: Option<String>,
+}
+
+impl Level {
+    fn is_array(&self) -> bool {
+        matches!(
+            self.state,
+            State::ArrayStart | State::ArrayValue | State::ArrayComma
+        )
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum State {
+    // `[`之后, 期待一个值或者`]`.
+    ArrayStart,
+    // `,`之后, 期待一个值.
+    ArrayValue,
+    // 值之后, 期待`,`或者`]`.
+    ArrayComma,
+    // `{`之后, 期待一个键或者`}`.
+    ObjectStart,
+    // `,`之后, 期待一个键.
+    ObjectKey,
+    // 键之后, 期待`:`.
+    ObjectColon,
+    // `:`之后, 期待一个值.
+    ObjectValue,
+    // 值之后, 期待`,`或者`}`.
+    ObjectComma,
+}
+
+// 顶层的值是否已经开始, 或者已经读完.
+#[derive(Clone, Copy, PartialEq)]
+enum Phase {
+    Start,
+    Value,
+    End,
+}
+
+enum Lexeme {
+    Token(Token),
+    // 恢复模式下, 已经被记录下来的词法错误.
+    Invalid,
+    End,
+    // 推送模式下需要更多的输入.
+    Suspend,
+}
+
+// 恢复模式下, 跳过出错的token之后停下来的位置.
+enum Sync {
+    Comma,
+    Close,
+}
+
+impl<'a> EventReader<'a> {
+    pub fn new(s: &'a str) -> Self {
+        Self::with_options(s, ParserOptions::default())
+    }
+
+    pub fn with_options(s: &'a str, options: ParserOptions) -> Self {
+        Self::from_source(Source::Text(Tokenizer::with_options(s, options)), options)
+    }
+
+    /// 从`reader`中边读边解析, 参见`Tokenizer::from_reader`.
+    pub fn from_reader<R: Read + 'a>(reader: R, options: ParserOptions) -> Self {
+        Self::from_source(
+            Source::Text(Tokenizer::from_reader(reader, options)),
+            options,
+        )
+    }
+
+    fn from_source(source: Source<'a>, options: ParserOptions) -> Self {
+        Self {
+            source,
+            options,
+            span: Span::default(),
+            pending: None,
+            stack: Vec::new(),
+            phase: Phase::Start,
+            recovering: false,
+            errors: Vec::new(),
+            eof: false,
+            failed: false,
+            suspended: false,
+        }
+    }
+
+    /// 读取下一个事件. 输入结束时返回`None`; 遇到错误时返回这个错误, 之后总是返回`None`.
+    pub fn next_event(&mut self) -> Option<Result<Event>> {
+        if self.failed {
+            return None;
+        }
+
+        match self.read_event() {
+            Ok(event) => event.map(Ok),
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+        }
+    }
+
+    /// 最近一个事件所属的值在文档中的位置, 用JSON Pointer(RFC 6901)表示, 顶层的值是`""`.
+    ///
+    /// 例如读到`{"a": [1, 2]}`中的`2`时是`"/a/1"`, 读到`Key("a")`时是`"/a"`.
+    pub fn pointer(&self) -> String {
+        let mut pointer = String::new();
+
+        for level in &self.stack {
+            if level.is_array() {
+                if level.count > 0 {
+                    pointer.push('/');
+                    pointer.push_str(&(level.count - 1).to_string());
+                }
+            } else if let Some(ref key) = level.key {
+                pointer.push('/');
+                pointer.push_str(&key.replace('~', "~0").replace('/', "~1"));
+            }
+        }
+
+        pointer
+    }
+
+    /// 最近读入的token在输入中的区间.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    // 以恢复模式读取: 语法错误被记录下来, 事件流会跳过出错的部分继续下去.
+    pub(crate) fn set_recovering(&mut self, recovering: bool) {
+        self.recovering = recovering;
+    }
+
+    pub(crate) fn take_errors(&mut self) -> Vec<JsonError> {
+        std::mem::take(&mut self.errors)
+    }
+
+    // 栈顶的对象当前是否有键, 恢复模式下出错的键会被丢弃, 它对应的值也就不再属于这个对象.
+    pub(crate) fn has_key(&self) -> bool {
+        self.stack.last().is_some_and(|level| level.key.is_some())
+    }
+
+    // 严格模式下直接返回错误; 恢复模式下只记录错误, 由调用者负责重新同步.
+    pub(crate) fn report(&mut self, error: JsonError) -> Result<()> {
+        if self.recovering {
+            self.errors.push(error);
+            Ok(())
+        } else {
+            Err(error)
+        }
+    }
+
+    pub(crate) fn eof_error(&self) -> JsonError {
+        let position = match self.source {
+            Source::Text(ref tokenizer) => tokenizer.position(),
+            Source::Push(ref buffer) => buffer.position(),
+        };
+
+        JsonError::UnexpectedEof { position }
+    }
+
+    // 推送模式: 输入由`buffer`陆续提供.
+    pub(crate) fn push(options: ParserOptions) -> EventReader<'static> {
+        EventReader::from_source(Source::Push(PushBuffer::new(options)), options)
+    }
+
+    pub(crate) fn buffer(&mut self) -> &mut PushBuffer {
+        match self.source {
+            Source::Push(ref mut buffer) => buffer,
+            Source::Text(_) => panic!("the reader is not in push mode"),
+        }
+    }
+
+    // 最近一次`next_event`返回`None`是因为需要更多的输入, 而不是输入已经结束.
+    pub(crate) fn is_suspended(&self) -> bool {
+        self.suspended
+    }
+
+    // 暂停时状态不会有任何改变, 提供更多的输入之后, 从同一个token重新开始.
+    fn read_event(&mut self) -> Result<Option<Event>> {
+        self.suspended = false;
+
+        loop {
+            if self.stack.is_empty() {
+                match self.phase {
+                    Phase::Start => {
+                        let event = self.read_value()?;
+                        if self.suspended {
+                            return Ok(None);
+                        }
+                        self.phase = Phase::Value;
+                        if event.is_some() {
+                            return Ok(event);
+                        }
+                    }
+                    Phase::Value => {
+                        self.parse_end()?;
+                        if !self.suspended {
+                            self.phase = Phase::End;
+                        }
+                        return Ok(None);
+                    }
+                    Phase::End => return Ok(None),
+                }
+            } else {
+                let event = self.step()?;
+                if event.is_some() || self.suspended {
+                    return Ok(event);
+                }
+            }
+        }
+    }
+
+    // 同一个文件结尾只报告一次.
+    fn report_eof(&mut self) -> Result<()> {
+        if self.eof {
+            return Ok(());
+        }
+        self.eof = true;
+        let error = self.eof_error();
+        self.report(error)
+    }
+
+    fn lex(&mut self) -> Result<Lexeme> {
+        if let Some((token, span)) = self.pending.take() {
+            self.span = span;
+            return Ok(Lexeme::Token(token));
+        }
+
+        match self.next_token() {
+            Some(Ok(token)) => {
+                self.span = token.span;
+                Ok(Lexeme::Token(token.token))
+            }
+            Some(Err(e)) if is_fatal(&e) => Err(e),
+            Some(Err(e)) => {
+                self.report(e)?;
+                Ok(Lexeme::Invalid)
+            }
+            None if self.suspended => Ok(Lexeme::Suspend),
+            None => Ok(Lexeme::End),
+        }
+    }
+
+    // 跳过注释. 推送模式下需要更多的输入时设置`suspended`并返回`None`.
+    fn next_token(&mut self) -> Option<Result<SpannedToken>> {
+        loop {
+            let token = match self.source {
+                Source::Text(ref mut tokenizer) => tokenizer.next_spanned(),
+                Source::Push(ref mut buffer) => match buffer.next_spanned() {
+                    Some(token) => token,
+                    None => {
+                        self.suspended = true;
+                        return None;
+                    }
+                },
+            };
+
+            match token {
+                Some(Ok(ref token)) if token.token.is_trivia() => continue,
+                token => return token,
+            }
+        }
+    }
+
+    fn parse_end(&mut self) -> Result<()> {
+        let position = match self.pending.take() {
+            Some((_, span)) => span.start,
+            None => match self.next_token() {
+                None => return Ok(()),
+                Some(Ok(token)) => token.span.start,
+                Some(Err(e)) if is_fatal(&e) => return Err(e),
+                Some(Err(e)) => e.position(),
+            },
+        };
+
+        self.report(JsonError::TrailingCharacters { position })
+    }
+
+    // 恢复模式下, 这个逗号本身已经作为多余的token报告过了, 例如`{,}`.
+    fn is_reported(&self, comma: Span) -> bool {
+        self.errors.last().is_some_and(|error| {
+            matches!(*error, JsonError::UnexpectedToken { token: Token::Comma, span } if span == comma)
+        })
+    }
+
+    fn unexpected(&self, token: Token) -> JsonError {
+        JsonError::UnexpectedToken {
+            token,
+            span: self.span,
+        }
+    }
+
+    // 跳过出错的token以及之后的内容, 直到当前这一层的`,`或者右括号.
+    // 如果遇到的右括号属于外层的容器, 就把它放回去, 让外层的容器来关闭.
+    fn synchronize(&mut self, token: Token) -> Result<Sync> {
+        let mut depth = 0;
+        let mut token = token;
+
+        loop {
+            match token {
+                Token::Comma if depth == 0 => return Ok(Sync::Comma),
+                Token::BracketOn | Token::BraceOn => depth += 1,
+                Token::BracketOff | Token::BraceOff if depth > 0 => depth -= 1,
+                Token::BracketOff | Token::BraceOff => {
+                    let closes = |level: &Level| {
+                        if level.is_array() {
+                            token == Token::BracketOff
+                        } else {
+                            token == Token::BraceOff
+                        }
+                    };
+                    if self.stack.last().is_some_and(closes) {
+                        return Ok(Sync::Close);
+                    }
+                    if self.stack.iter().any(closes) {
+                        self.pending = Some((token, self.span));
+                        return Ok(Sync::Close);
+                    }
+                }
+                _ => {}
+            }
+
+            token = loop {
+                match self.lex()? {
+                    Lexeme::Token(token) => break token,
+                    Lexeme::Invalid => continue,
+                    Lexeme::End => {
+                        self.report_eof()?;
+                        return Ok(Sync::Close);
+                    }
+                    Lexeme::Suspend => unreachable!("push mode never recovers"),
+                }
+            };
+        }
+    }
+
+    fn limit_exceeded(&self, limit: Limit) -> JsonError {
+        JsonError::LimitExceeded {
+            limit,
+            position: self.span.start,
+        }
+    }
+
+    // 超出上限的错误是无法恢复的, 即使在恢复模式下也会直接返回.
+    fn enter(&mut self, state: State) -> Result<()> {
+        if self.stack.len() >= self.options.max_depth {
+            return Err(self.limit_exceeded(Limit::Depth));
+        }
+
+        self.stack.push(Level {
+            state,
+            comma: self.span,
+            count: 0,
+            key: None,
+        });
+
+        Ok(())
+    }
+
+    fn leave(&mut self) -> Option<Event> {
+        let level = self.stack.pop()?;
+        self.complete();
+
+        Some(if level.is_array() {
+            Event::EndArray
+        } else {
+            Event::EndObject
+        })
+    }
+
+    fn top(&mut self) -> &mut Level {
+        self.stack
+            .last_mut()
+            .expect("the reader stack must not be empty here")
+    }
+
+    // 栈顶的数组或者对象开始了一个新的元素.
+    fn start_element(&mut self) -> Result<()> {
+        let max_elements = self.options.max_elements;
+
+        if let Some(level) = self.stack.last_mut() {
+            level.count += 1;
+            if level.count > max_elements {
+                return Err(self.limit_exceeded(Limit::ElementCount));
+            }
+        }
+        Ok(())
+    }
+
+    // 栈顶的数组或者对象的当前元素读完了(恢复模式下可能是一个无效的值).
+    fn complete(&mut self) {
+        if let Some(level) = self.stack.last_mut() {
+            level.state = if level.is_array() {
+                State::ArrayComma
+            } else {
+                State::ObjectComma
+            };
+        }
+    }
+
+    fn read_value(&mut self) -> Result<Option<Event>> {
+        match self.lex()? {
+            Lexeme::Token(token) => self.begin(token),
+            Lexeme::Invalid | Lexeme::Suspend => Ok(None),
+            Lexeme::End => {
+                self.report_eof()?;
+                Ok(None)
+            }
+        }
+    }
+
+    // 值的第一个token: 标量直接得到结果, 左括号则在栈上开始一个新的数组或者对象.
+    fn begin(&mut self, token: Token) -> Result<Option<Event>> {
+        let event = match token {
+            Token::Null => Event::Scalar(Json::Null),
+            Token::String(s) => Event::Scalar(Json::String(s)),
+            Token::Number(n) => Event::Scalar(Json::Number(n)),
+            Token::Boolean(b) => Event::Scalar(Json::Boolean(b)),
+            Token::Identifier(ref name)
+                if self.options.allow_nan_infinity && name == "Infinity" =>
+            {
+                Event::Scalar(Json::from(f64::INFINITY))
+            }
+            Token::Identifier(ref name) if self.options.allow_nan_infinity && name == "NaN" => {
+                Event::Scalar(Json::from(f64::NAN))
+            }
+            Token::BracketOn => Event::StartArray,
+            Token::BraceOn => Event::StartObject,
+            _ => {
+                let error = self.unexpected(token);
+                self.report(error)?;
+                self.complete();
+                return Ok(None);
+            }
+        };
+
+        self.start_element()?;
+        match event {
+            Event::StartArray => self.enter(State::ArrayStart)?,
+            Event::StartObject => self.enter(State::ObjectStart)?,
+            _ => self.complete(),
+        }
+
+        Ok(Some(event))
+    }
+
+    // 为栈顶的数组或者对象读入下一个token, 返回`None`表示这个token没有产生事件.
+    fn step(&mut self) -> Result<Option<Event>> {
+        let state = self.top().state;
+
+        let token = match self.lex()? {
+            Lexeme::Token(token)
+                if state == State::ObjectStart
+                    || state == State::ObjectKey
+                    || state == State::ObjectComma =>
+            {
+                self.unquoted_key(token)
+            }
+            Lexeme::Token(token) => token,
+            // 出错的token如果出现在值的位置上, 就当作一个无效的值.
+            Lexeme::Invalid => {
+                match state {
+                    State::ArrayStart | State::ArrayValue | State::ObjectValue => self.complete(),
+                    State::ObjectStart | State::ObjectKey => {
+                        let level = self.top();
+                        level.key = None;
+                        level.state = State::ObjectColon;
+                    }
+                    _ => {}
+                }
+                return Ok(None);
+            }
+            Lexeme::End => {
+                self.report_eof()?;
+                return Ok(self.leave());
+            }
+            Lexeme::Suspend => return Ok(None),
+        };
+
+        match (state, token) {
+            (State::ArrayStart, Token::BracketOff)
+            | (State::ArrayComma, Token::BracketOff)
+            | (State::ObjectStart, Token::BraceOff)
+            | (State::ObjectComma, Token::BraceOff) => Ok(self.leave()),
+            (State::ArrayValue, Token::BracketOff) | (State::ObjectKey, Token::BraceOff) => {
+                let comma = self.top().comma;
+                if !self.options.allow_trailing_commas && !self.is_reported(comma) {
+                    self.report(JsonError::TrailingComma {
+                        position: comma.start,
+                    })?;
+                }
+                Ok(self.leave())
+            }
+            (State::ArrayComma, Token::Comma) | (State::ObjectComma, Token::Comma) => {
+                let span = self.span;
+                let level = self.top();
+                level.comma = span;
+                level.state = if state == State::ArrayComma {
+                    State::ArrayValue
+                } else {
+                    State::ObjectKey
+                };
+                Ok(None)
+            }
+            (State::ArrayComma, token) if is_value_start(&token) => {
+                self.report(JsonError::MissingComma {
+                    position: self.span.start,
+                })?;
+                self.begin(token)
+            }
+            (State::ArrayStart, token)
+            | (State::ArrayValue, token)
+            | (State::ObjectValue, token)
+                if is_value_start(&token) =>
+            {
+                self.begin(token)
+            }
+            (State::ObjectComma, Token::String(key)) => {
+                self.report(JsonError::MissingComma {
+                    position: self.span.start,
+                })?;
+                Ok(Some(self.set_key(key)))
+            }
+            (State::ObjectStart, Token::String(key)) | (State::ObjectKey, Token::String(key)) => {
+                Ok(Some(self.set_key(key)))
+            }
+            (State::ObjectColon, Token::Colon) => {
+                self.top().state = State::ObjectValue;
+                Ok(None)
+            }
+            (_, token) => {
+                let error = self.unexpected(token.clone());
+                self.report(error)?;
+                self.recover(token)
+            }
+        }
+    }
+
+    // 只有打开了`allow_unquoted_keys`, tokenizer才会给出标识符, 这时`true`, `false`和`null`也可以作为键.
+    fn unquoted_key(&self, token: Token) -> Token {
+        match token {
+            Token::Identifier(key) => Token::String(key),
+            Token::Boolean(b) if self.options.allow_unquoted_keys => Token::String(b.to_string()),
+            Token::Null if self.options.allow_unquoted_keys => Token::String(String::from("null")),
+            token => token,
+        }
+    }
+
+    fn set_key(&mut self, key: String) -> Event {
+        let level = self.top();
+        level.key = Some(key.clone());
+        level.state = State::ObjectColon;
+
+        Event::Key(key)
+    }
+
+    fn recover(&mut self, token: Token) -> Result<Option<Event>> {
+        self.top().key = None;
+
+        match self.synchronize(token)? {
+            Sync::Comma => {
+                let span = self.span;
+                let level = self.top();
+                level.comma = span;
+                level.state = if level.is_array() {
+                    State::ArrayValue
+                } else {
+                    State::ObjectKey
+                };
+                Ok(None)
+            }
+            Sync::Close => Ok(self.leave()),
+        }
+    }
+}
+
+impl<'a> Iterator for EventReader<'a> {
+    type Item = Result<Event>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_event()
+    }
+}
+
+fn is_value_start(token: &Token) -> bool {
+    match *token {
+        Token::String(_)
+        | Token::Number(_)
+        | Token::Boolean(_)
+        | Token::Null
+        | Token::BracketOn
+        | Token::BraceOn => true,
+        Token::Identifier(ref name) => name == "Infinity" || name == "NaN",
+        Token::Comma
+        | Token::Colon
+        | Token::BracketOff
+        | Token::BraceOff
+        | Token::LineComment(_)
+        | Token::BlockComment(_) => false,
+    }
+}
+
+// 超出上限, 读取失败以及无效的UTF-8编码都说明输入本身无法继续读下去, 它们不是语法错误, 即使在恢复模式下也直接返回.
+fn is_fatal(error: &JsonError) -> bool {
+    matches!(
+        *error,
+        JsonError::LimitExceeded { .. } | JsonError::Io { .. } | JsonError::InvalidUtf8 { .. }
+    )
+}
@@ -1,5 +1,7 @@
 use std::collections::HashMap;
 
+use crate::map::Map;
+use crate::number::Number;
 use crate::value::Json;
 
 macro_rules! impl_from_num_for_json {
@@ -7,7 +9,7 @@ macro_rules! impl_from_num_for_json {
         $(
             impl From<$t> for Json {
                 fn from(n: $t) -> Json {
-                    Json::Number(n as f64)
+                    Json::Number(n.into())
                 }
             }
         )*
@@ -16,6 +18,12 @@ macro_rules! impl_from_num_for_json {
 
 impl_from_num_for_json!(u8 i8 u16 i16 u32 i32 u64 i64 usize isize f32 f64);
 
+impl From<Number> for Json {
+    fn from(n: Number) -> Json {
+        Json::Number(n)
+    }
+}
+
 impl From<bool> for Json {
     fn from(b: bool) -> Json {
         Json::Boolean(b)
@@ -41,13 +49,13 @@ impl From<Vec<Json>> for Json {
 }
 
 impl From<HashMap<String, Json>> for Json {
-    fn from(mut map: HashMap<String, Json>) -> Self {
-        let mut object = HashMap::new();
-
-        for (key, value) in map.drain() {
-            object.insert(key, value);
-        }
+    fn from(map: HashMap<String, Json>) -> Self {
+        Json::Object(map.into())
+    }
+}
 
-        Json::Object(object)
+impl From<Map> for Json {
+    fn from(map: Map) -> Self {
+        Json::Object(map)
     }
 }
@@ -0,0 +1,163 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::str;
+
+use crate::error::JsonError;
+use crate::position::Position;
+
+const BUFFER_SIZE: usize = 8 * 1024;
+
+// 从`Read`中按UTF-8解码出字符, 自带缓冲区, 不需要再套一层`BufReader`.
+// 读取失败或者遇到无效的UTF-8编码时, 把错误保存下来, 之后就像输入结束了一样.
+pub(crate) struct ReadChars<'a> {
+    reader: Box<dyn Read + 'a>,
+    buffer: Box<[u8]>,
+    // `buffer[start..end]`是还没有解码的字节.
+    start: usize,
+    end: usize,
+    peeked: Option<Option<char>>,
+    error: Option<ReadError>,
+    eof: bool,
+}
+
+enum ReadError {
+    Io(io::Error),
+    InvalidUtf8,
+}
+
+impl<'a> ReadChars<'a> {
+    pub(crate) fn new<R: Read + 'a>(reader: R) -> Self {
+        Self {
+            reader: Box::new(reader),
+            buffer: vec![0; BUFFER_SIZE].into_boxed_slice(),
+            start: 0,
+            end: 0,
+            peeked: None,
+            error: None,
+            eof: false,
+        }
+    }
+
+    pub(crate) fn peek(&mut self) -> Option<char> {
+        if self.peeked.is_none() {
+            self.peeked = Some(self.decode());
+        }
+        self.peeked.flatten()
+    }
+
+    pub(crate) fn next(&mut self) -> Option<char> {
+        match self.peeked.take() {
+            Some(ch) => ch,
+            None => self.decode(),
+        }
+    }
+
+    // 取出读取时遇到的错误, `position`是出错时tokenizer所在的位置.
+    pub(crate) fn take_error(&mut self, position: Position) -> Option<JsonError> {
+        self.error.take().map(|error| match error {
+            ReadError::Io(error) => JsonError::Io { error, position },
+            ReadError::InvalidUtf8 => JsonError::InvalidUtf8 { position },
+        })
+    }
+
+    fn decode(&mut self) -> Option<char> {
+        if !self.fill(1) {
+            return None;
+        }
+
+        let width = match self.buffer[self.start] {
+            0x00..=0x7F => 1,
+            0xC2..=0xDF => 2,
+            0xE0..=0xEF => 3,
+            0xF0..=0xF4 => 4,
+            _ => return self.invalid(),
+        };
+        if !self.fill(width) {
+            // 输入在一个字符的中间结束了.
+            return self.invalid();
+        }
+
+        let bytes = &self.buffer[self.start..self.start + width];
+        match str::from_utf8(bytes) {
+            Ok(s) => {
+                self.start += width;
+                s.chars().next()
+            }
+            Err(_) => self.invalid(),
+        }
+    }
+
+    fn invalid(&mut self) -> Option<char> {
+        if self.error.is_none() {
+            self.error = Some(ReadError::InvalidUtf8);
+        }
+        self.start = self.end;
+        self.eof = true;
+        None
+    }
+
+    // 保证缓冲区中至少有`count`个未解码的字节, 输入已经结束或者读取失败时返回`false`.
+    // 未解码的字节会先移到缓冲区的开头, 所以一个字符不会被缓冲区的边界分开.
+    fn fill(&mut self, count: usize) -> bool {
+        if self.end - self.start >= count {
+            return true;
+        }
+        if self.eof {
+            return false;
+        }
+
+        self.buffer.copy_within(self.start..self.end, 0);
+        self.end -= self.start;
+        self.start = 0;
+
+        while self.end < count {
+            match self.reader.read(&mut self.buffer[self.end..]) {
+                Ok(0) => {
+                    self.eof = true;
+                    return false;
+                }
+                Ok(n) => self.end += n,
+                Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    self.error = Some(ReadError::Io(e));
+                    self.eof = true;
+                    return false;
+                }
+            }
+        }
+
+        true
+    }
+}
+
+// 让`CodeGenerator`可以直接写进`io::Write`. `fmt::Write`的错误不带任何信息, 所以把`io::Error`保存下来.
+pub(crate) struct IoWriter<W> {
+    writer: W,
+    error: Option<io::Error>,
+}
+
+impl<W: Write> IoWriter<W> {
+    pub(crate) fn new(writer: W) -> Self {
+        Self {
+            writer,
+            error: None,
+        }
+    }
+
+    // 取出写入时遇到的错误.
+    pub(crate) fn take_error(&mut self) -> Option<JsonError> {
+        self.error.take().map(|error| JsonError::Io {
+            error,
+            position: Position::start(),
+        })
+    }
+}
+
+impl<W: Write> fmt::Write for IoWriter<W> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.writer.write_all(s.as_bytes()).map_err(|e| {
+            self.error = Some(e);
+            fmt::Error
+        })
+    }
+}
@@ -1,31 +1,118 @@
 pub mod code_generator;
+pub mod diagnostic;
 pub mod error;
+pub mod event_reader;
 pub mod implement;
+mod io;
 pub mod macros;
+pub mod map;
+pub mod number;
 pub mod parser;
+pub mod position;
+pub mod push_parser;
 pub mod token;
 pub mod tokenizer;
 pub mod value;
 
-use crate::code_generator::CodeGenerator;
+use std::fmt;
+use std::io::{Read, Write};
+
+use crate::code_generator::{CodeGenerator, GeneratorOptions, PrettyConfig};
 use crate::error::JsonError;
-use crate::parser::Parser;
+use crate::io::IoWriter;
+use crate::parser::{Parser, ParserOptions};
 use crate::value::Json;
 
 pub type Result<T> = std::result::Result<T, JsonError>;
 
-fn do_nothing() {}
-
-pub fn parse(s: &str) -> Json {
+pub fn parse(s: &str) -> Result<Json> {
     let mut parser = Parser::new(s);
     parser.parse()
 }
 
+pub fn parse_with(s: &str, options: ParserOptions) -> Result<Json> {
+    let mut parser = Parser::with_options(s, options);
+    parser.parse()
+}
+
+/// 从`reader`中边读边解析, 适用于文件, 标准输入和网络连接, 不需要先把它们读进一个`String`.
+/// 读取失败时返回`JsonError::Io`.
+pub fn from_reader<R: Read>(reader: R) -> Result<Json> {
+    from_reader_with(reader, ParserOptions::default())
+}
+
+pub fn from_reader_with<R: Read>(reader: R, options: ParserOptions) -> Result<Json> {
+    let mut parser = Parser::from_reader(reader, options);
+    parser.parse()
+}
+
+/// 解析`s`开头的一个JSON值, 返回这个值以及它所占的字节数, 适用于JSON嵌在其他文本中的场景.
+pub fn parse_prefix(s: &str) -> Result<(Json, usize)> {
+    let mut parser = Parser::new(s);
+    parser.parse_prefix()
+}
+
+/// 以恢复模式解析`s`, 返回尽可能完整的解析结果以及找到的所有语法错误.
+pub fn parse_recovering(s: &str) -> (Option<Json>, Vec<JsonError>) {
+    let mut parser = Parser::new(s);
+    parser.parse_recovering()
+}
+
+/// `NaN`和正负无穷会被写成`null`, 所以总是成功的.
 pub fn stringify<T>(o: T) -> String
 where
     T: Into<Json>,
 {
-    let mut gen = CodeGenerator::new();
-    gen.gather(&o.into());
-    gen.product()
+    stringify_with(o, GeneratorOptions::new()).expect("the default options never fail")
+}
+
+pub fn stringify_pretty<T>(o: T) -> String
+where
+    T: Into<Json>,
+{
+    stringify_with(o, GeneratorOptions::new().pretty(PrettyConfig::new()))
+        .expect("the default options never fail")
+}
+
+/// 只有在`NonFinite::Error`策略下遇到`NaN`或者正负无穷时才会返回错误.
+pub fn stringify_with<T>(o: T, options: GeneratorOptions) -> Result<String>
+where
+    T: Into<Json>,
+{
+    let mut gen = CodeGenerator::with_options(options);
+    gen.gather(&o.into())?;
+    Ok(gen.product())
+}
+
+/// 把`json`直接写进`writer`, 不经过中间的`String`. `writer`没有缓冲时, 最好先套一层`BufWriter`.
+/// 写入失败时返回`JsonError::Io`.
+pub fn to_writer<W: Write>(writer: W, json: &Json) -> Result<()> {
+    to_writer_with(writer, json, GeneratorOptions::new())
+}
+
+pub fn to_writer_pretty<W: Write>(writer: W, json: &Json) -> Result<()> {
+    to_writer_with(
+        writer,
+        json,
+        GeneratorOptions::new().pretty(PrettyConfig::new()),
+    )
+}
+
+pub fn to_writer_with<W: Write>(writer: W, json: &Json, options: GeneratorOptions) -> Result<()> {
+    let mut gen = CodeGenerator::from_writer(IoWriter::new(writer), options);
+    let result = gen.gather(json);
+
+    match gen.into_inner().take_error() {
+        Some(error) => Err(error),
+        None => result,
+    }
+}
+
+/// 把`json`直接写进一个`fmt::Write`, 例如`fmt::Formatter`.
+pub fn to_fmt_writer<W: fmt::Write>(
+    writer: W,
+    json: &Json,
+    options: GeneratorOptions,
+) -> Result<()> {
+    CodeGenerator::from_writer(writer, options).gather(json)
 }
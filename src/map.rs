@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::iter::FromIterator;
+use std::ops::{Index, IndexMut};
+use std::slice;
+use std::vec;
+
+use crate::value::Json;
+
+/// `Json::Object`所使用的有序map.
+///
+/// 键值对按照插入的先后顺序保存在`entries`中, 同时用`indices`记录每个键在`entries`中的下标,
+/// 所以查找是O(1)的, 遍历的顺序就是插入的顺序. 对已经存在的键再次插入只会替换它的值, 不会改变它的位置.
+#[derive(Debug, Clone, Default)]
+pub struct Map {
+    entries: Vec<(String, Json)>,
+    indices: HashMap<String, usize>,
+}
+
+impl Map {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::with_capacity(capacity),
+            indices: HashMap::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.indices.clear();
+    }
+
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.indices.contains_key(key)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&Json> {
+        let &index = self.indices.get(key)?;
+        Some(&self.entries[index].1)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Json> {
+        let &index = self.indices.get(key)?;
+        Some(&mut self.entries[index].1)
+    }
+
+    pub(crate) fn index_of(&self, key: &str) -> Option<usize> {
+        self.indices.get(key).copied()
+    }
+
+    /// 插入一个键值对, 如果键已经存在, 返回旧的值.
+    pub fn insert(&mut self, key: String, value: Json) -> Option<Json> {
+        match self.indices.get(&key) {
+            Some(&index) => Some(std::mem::replace(&mut self.entries[index].1, value)),
+            None => {
+                self.indices.insert(key.clone(), self.entries.len());
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// 删除一个键, 其余键值对的相对顺序保持不变, 所以这个操作是O(n)的.
+    pub fn remove(&mut self, key: &str) -> Option<Json> {
+        let index = self.indices.remove(key)?;
+        let (_, value) = self.entries.remove(index);
+
+        for (key, _) in &self.entries[index..] {
+            if let Some(i) = self.indices.get_mut(key) {
+                *i -= 1;
+            }
+        }
+
+        Some(value)
+    }
+
+    /// 按插入顺序取出所有的键值对, map随后变为空的.
+    pub fn drain(&mut self) -> vec::Drain<'_, (String, Json)> {
+        self.indices.clear();
+        self.entries.drain(..)
+    }
+
+    pub fn iter(&self) -> Iter<'_> {
+        Iter {
+            inner: self.entries.iter(),
+        }
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_> {
+        IterMut {
+            inner: self.entries.iter_mut(),
+        }
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &String> {
+        self.entries.iter().map(|(key, _)| key)
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &Json> {
+        self.entries.iter().map(|(_, value)| value)
+    }
+
+    pub fn values_mut(&mut self) -> impl Iterator<Item = &mut Json> {
+        self.entries.iter_mut().map(|(_, value)| value)
+    }
+}
+
+/// 两个map包含相同的键值对就认为它们相等, 与插入的顺序无关.
+impl PartialEq for Map {
+    fn eq(&self, other: &Map) -> bool {
+        self.len() == other.len()
+            && self
+                .iter()
+                .all(|(key, value)| other.get(key) == Some(value))
+    }
+}
+
+impl<'a> Index<&'a str> for Map {
+    type Output = Json;
+
+    fn index(&self, key: &'a str) -> &Json {
+        self.get(key).expect("key not found in Map")
+    }
+}
+
+impl<'a> IndexMut<&'a str> for Map {
+    fn index_mut(&mut self, key: &'a str) -> &mut Json {
+        self.get_mut(key).expect("key not found in Map")
+    }
+}
+
+impl FromIterator<(String, Json)> for Map {
+    fn from_iter<I: IntoIterator<Item = (String, Json)>>(iter: I) -> Self {
+        let mut map = Map::new();
+        map.extend(iter);
+        map
+    }
+}
+
+impl Extend<(String, Json)> for Map {
+    fn extend<I: IntoIterator<Item = (String, Json)>>(&mut self, iter: I) {
+        for (key, value) in iter {
+            self.insert(key, value);
+        }
+    }
+}
+
+impl From<HashMap<String, Json>> for Map {
+    fn from(map: HashMap<String, Json>) -> Self {
+        map.into_iter().collect()
+    }
+}
+
+pub struct Iter<'a> {
+    inner: slice::Iter<'a, (String, Json)>,
+}
+
+impl<'a> Iterator for Iter<'a> {
+    type Item = (&'a String, &'a Json);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, value)| (key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for Iter<'a> {}
+
+pub struct IterMut<'a> {
+    inner: slice::IterMut<'a, (String, Json)>,
+}
+
+impl<'a> Iterator for IterMut<'a> {
+    type Item = (&'a String, &'a mut Json);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(key, value)| (&*key, value))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a> ExactSizeIterator for IterMut<'a> {}
+
+pub struct IntoIter {
+    inner: vec::IntoIter<(String, Json)>,
+}
+
+impl Iterator for IntoIter {
+    type Item = (String, Json);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl ExactSizeIterator for IntoIter {}
+
+impl IntoIterator for Map {
+    type Item = (String, Json);
+    type IntoIter = IntoIter;
+
+    fn into_iter(self) -> IntoIter {
+        IntoIter {
+            inner: self.entries.into_iter(),
+        }
+    }
+}
+
+impl<'a> IntoIterator for &'a Map {
+    type Item = (&'a String, &'a Json);
+    type IntoIter = Iter<'a>;
+
+    fn into_iter(self) -> Iter<'a> {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut Map {
+    type Item = (&'a String, &'a mut Json);
+    type IntoIter = IterMut<'a>;
+
+    fn into_iter(self) -> IterMut<'a> {
+        self.iter_mut()
+    }
+}
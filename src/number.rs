@@ -0,0 +1,208 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::error::JsonError;
+use crate::parser::ParserOptions;
+use crate::position::Position;
+use crate::token::{SpannedToken, Token};
+use crate::tokenizer::Tokenizer;
+
+/// JSON中的数字.
+///
+/// 整数会尽量保存为`u64`或者`i64`, 只有带小数点或者指数的数字, 以及超出64位整数范围的数字才会保存为`f64`,
+/// 这样, 像数据库ID这样大于2^53的整数在解析和生成的过程中都不会丢失精度.
+///
+/// 在`ParserOptions::arbitrary_precision`模式下, 数字会保存它在输入中的原始文本, 需要的时候再进行转换.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Number {
+    n: N,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum N {
+    PosInt(u64),
+    NegInt(i64),
+    Float(f64),
+    Raw(String),
+}
+
+impl Number {
+    pub fn from_f64(f: f64) -> Self {
+        Self { n: N::Float(f) }
+    }
+
+    pub fn is_i64(&self) -> bool {
+        self.as_i64().is_some()
+    }
+
+    pub fn is_u64(&self) -> bool {
+        self.as_u64().is_some()
+    }
+
+    pub fn is_f64(&self) -> bool {
+        match self.n {
+            N::Float(_) => true,
+            N::Raw(ref text) => !is_integer(text),
+            _ => false,
+        }
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        match self.n {
+            N::PosInt(u) if u <= i64::MAX as u64 => Some(u as i64),
+            N::NegInt(i) => Some(i),
+            N::Raw(ref text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        match self.n {
+            N::PosInt(u) => Some(u),
+            N::Raw(ref text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// 整数转换成`f64`时可能会丢失精度.
+    pub fn as_f64(&self) -> Option<f64> {
+        match self.n {
+            N::PosInt(u) => Some(u as f64),
+            N::NegInt(i) => Some(i as f64),
+            N::Float(f) => Some(f),
+            N::Raw(ref text) => text.parse().ok(),
+        }
+    }
+
+    pub fn as_i128(&self) -> Option<i128> {
+        match self.n {
+            N::PosInt(u) => Some(i128::from(u)),
+            N::NegInt(i) => Some(i128::from(i)),
+            N::Float(_) => None,
+            N::Raw(ref text) => text.parse().ok(),
+        }
+    }
+
+    pub fn as_u128(&self) -> Option<u128> {
+        match self.n {
+            N::PosInt(u) => Some(u128::from(u)),
+            N::Raw(ref text) => text.parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// 数字在输入中的原始文本, 只有在arbitrary_precision模式下解析出来的数字才有.
+    pub fn as_str(&self) -> Option<&str> {
+        match self.n {
+            N::Raw(ref text) => Some(text),
+            _ => None,
+        }
+    }
+
+    // 由Tokenizer调用, 此时`text`已经是一个符合JSON语法的数字了.
+    // 超出`f64`范围的数字会返回`None`, 否则它会变成无穷大, 写回去时就成了`null`.
+    pub(crate) fn from_lexeme(text: &str) -> Option<Self> {
+        if is_integer(text) {
+            if text.starts_with('-') {
+                match text.parse::<i64>() {
+                    Ok(0) => {}
+                    Ok(i) => return Some(Self { n: N::NegInt(i) }),
+                    Err(_) => {}
+                }
+            } else if let Ok(u) = text.parse::<u64>() {
+                return Some(Self { n: N::PosInt(u) });
+            }
+        }
+
+        text.parse::<f64>()
+            .ok()
+            .filter(|f| f.is_finite())
+            .map(Self::from_f64)
+    }
+
+    pub(crate) fn from_raw(text: String) -> Self {
+        Self { n: N::Raw(text) }
+    }
+}
+
+fn is_integer(text: &str) -> bool {
+    !text.contains(['.', 'e', 'E'])
+}
+
+/// 按照JSON的数字语法解析文本, 并保留原始文本.
+impl FromStr for Number {
+    type Err = JsonError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let options = ParserOptions::new().arbitrary_precision(true);
+        let mut tokenizer = Tokenizer::with_options(s, options);
+
+        match tokenizer.next_spanned() {
+            Some(Ok(SpannedToken {
+                token: Token::Number(number),
+                span,
+            })) if span.start.offset == 0 && span.end.offset == s.len() => Ok(number),
+            Some(Err(e @ JsonError::InvalidNumber { .. })) => Err(e),
+            _ => Err(JsonError::InvalidNumber {
+                number: s.to_owned(),
+                position: Position::start(),
+            }),
+        }
+    }
+}
+
+impl fmt::Display for Number {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.n {
+            N::PosInt(u) => write!(f, "{}", u),
+            N::NegInt(i) => write!(f, "{}", i),
+            // 与JavaScript一样, 很大和很小的数字使用指数形式, 两种形式都是能够原样解析回来的最短表示.
+            N::Float(n) if n != 0.0 && (n.abs() >= 1e21 || n.abs() < 1e-6) => write!(f, "{:e}", n),
+            N::Float(n) => write!(f, "{}", n),
+            N::Raw(ref text) => f.write_str(text),
+        }
+    }
+}
+
+macro_rules! impl_from_unsigned_for_number {
+    ($($t:ident)*) => {
+        $(
+            impl From<$t> for Number {
+                fn from(u: $t) -> Number {
+                    Number { n: N::PosInt(u as u64) }
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! impl_from_signed_for_number {
+    ($($t:ident)*) => {
+        $(
+            impl From<$t> for Number {
+                fn from(i: $t) -> Number {
+                    if i < 0 {
+                        Number { n: N::NegInt(i as i64) }
+                    } else {
+                        Number { n: N::PosInt(i as u64) }
+                    }
+                }
+            }
+        )*
+    };
+}
+
+impl_from_unsigned_for_number!(u8 u16 u32 u64 usize);
+impl_from_signed_for_number!(i8 i16 i32 i64 isize);
+
+impl From<f32> for Number {
+    fn from(f: f32) -> Number {
+        Number::from_f64(f64::from(f))
+    }
+}
+
+impl From<f64> for Number {
+    fn from(f: f64) -> Number {
+        Number::from_f64(f)
+    }
+}
@@ -1,97 +1,444 @@
-use std::collections::HashMap;
+use std::io::Read;
 
-use crate::do_nothing;
-use crate::token::Token;
-use crate::tokenizer::Tokenizer;
+use crate::error::JsonError;
+use crate::event_reader::{Event, EventReader};
+use crate::map::Map;
+use crate::position::Span;
 use crate::value::Json;
+use crate::Result;
 
+/// 默认的最大嵌套深度.
+pub const DEFAULT_MAX_DEPTH: usize = 128;
+
+/// 对象中出现重复的键时的处理方式.
+///
+/// 不同的JSON实现对重复的键有不同的处理, 当几个组件对同一份输入各自取了不同的值时, 就可能被利用来绕过校验,
+/// 所以处理不可信的输入时最好使用`Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateKeys {
+    /// 报告`JsonError::DuplicateKey`错误.
+    Error,
+    /// 保留第一次出现的值.
+    FirstWins,
+    /// 保留最后一次出现的值, 键的位置仍然是第一次出现的位置.
+    LastWins,
+    /// 把所有出现过的值按顺序收集到一个数组中.
+    CollectAll,
+}
+
+/// 控制`Parser`和`Tokenizer`行为的选项.
+///
+/// 默认只接受RFC 8259规定的严格的JSON, 各个`allow_*`开关可以分别放开一些常见的非标准写法.
+///
+/// 解析不可信的输入时, 各种上限可以防止栈溢出以及内存耗尽. 除了嵌套深度以外, 其余的上限默认都不做限制.
+#[derive(Debug, Clone, Copy)]
+pub struct ParserOptions {
+    pub(crate) arbitrary_precision: bool,
+    pub(crate) allow_comments: bool,
+    pub(crate) allow_trailing_commas: bool,
+    pub(crate) allow_single_quotes: bool,
+    pub(crate) allow_unquoted_keys: bool,
+    pub(crate) allow_nan_infinity: bool,
+    pub(crate) allow_leading_plus: bool,
+    pub(crate) allow_hex_numbers: bool,
+    pub(crate) allow_bare_decimal_points: bool,
+    pub(crate) allow_line_continuations: bool,
+    pub(crate) allow_extended_escapes: bool,
+    pub(crate) allow_extended_whitespace: bool,
+    pub(crate) emit_comments: bool,
+    pub(crate) max_depth: usize,
+    pub(crate) max_input_size: usize,
+    pub(crate) max_string_length: usize,
+    pub(crate) max_elements: usize,
+    pub(crate) duplicate_keys: DuplicateKeys,
+}
+
+impl Default for ParserOptions {
+    fn default() -> Self {
+        Self {
+            arbitrary_precision: false,
+            allow_comments: false,
+            allow_trailing_commas: false,
+            allow_single_quotes: false,
+            allow_unquoted_keys: false,
+            allow_nan_infinity: false,
+            allow_leading_plus: false,
+            allow_hex_numbers: false,
+            allow_bare_decimal_points: false,
+            allow_line_continuations: false,
+            allow_extended_escapes: false,
+            allow_extended_whitespace: false,
+            emit_comments: false,
+            max_depth: DEFAULT_MAX_DEPTH,
+            max_input_size: usize::MAX,
+            max_string_length: usize::MAX,
+            max_elements: usize::MAX,
+            duplicate_keys: DuplicateKeys::LastWins,
+        }
+    }
+}
+
+impl ParserOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 严格按照RFC 8259解析, 与`ParserOptions::default()`相同.
+    pub fn strict() -> Self {
+        Self::default()
+    }
+
+    /// 像VS Code的settings.json那样的JSONC: 允许注释以及末尾的逗号.
+    pub fn jsonc() -> Self {
+        Self::default()
+            .allow_comments(true)
+            .allow_trailing_commas(true)
+    }
+
+    /// 打开所有的`allow_*`开关, 适用于手写的配置文件.
+    pub fn lenient() -> Self {
+        Self::json5()
+    }
+
+    /// 按照JSON5(https://spec.json5.org)解析.
+    pub fn json5() -> Self {
+        Self::default()
+            .allow_comments(true)
+            .allow_trailing_commas(true)
+            .allow_single_quotes(true)
+            .allow_unquoted_keys(true)
+            .allow_nan_infinity(true)
+            .allow_leading_plus(true)
+            .allow_hex_numbers(true)
+            .allow_bare_decimal_points(true)
+            .allow_line_continuations(true)
+            .allow_extended_escapes(true)
+            .allow_extended_whitespace(true)
+    }
+
+    /// 允许`// ...`行注释和`/* ... */`块注释, 它们和空白字符一样被跳过.
+    pub fn allow_comments(mut self, allow_comments: bool) -> Self {
+        self.allow_comments = allow_comments;
+        self
+    }
+
+    /// 允许注释时, 让`Tokenizer`把注释作为`Token::LineComment`和`Token::BlockComment`给出, 而不是直接跳过,
+    /// 方便需要保留注释的工具使用. `Parser`总是会忽略这些token.
+    pub fn emit_comments(mut self, emit_comments: bool) -> Self {
+        self.emit_comments = emit_comments;
+        self
+    }
+
+    /// 允许数组和对象的最后一个元素后面有一个逗号, 例如`[1, 2,]`.
+    pub fn allow_trailing_commas(mut self, allow_trailing_commas: bool) -> Self {
+        self.allow_trailing_commas = allow_trailing_commas;
+        self
+    }
+
+    /// 允许用单引号括起来的字符串, 例如`'abc'`, 其中可以使用`\'`转义.
+    pub fn allow_single_quotes(mut self, allow_single_quotes: bool) -> Self {
+        self.allow_single_quotes = allow_single_quotes;
+        self
+    }
+
+    /// 允许对象的键是不带引号的标识符, 例如`{key: 1}`.
+    pub fn allow_unquoted_keys(mut self, allow_unquoted_keys: bool) -> Self {
+        self.allow_unquoted_keys = allow_unquoted_keys;
+        self
+    }
+
+    /// 允许`NaN`, `Infinity`以及`-Infinity`.
+    pub fn allow_nan_infinity(mut self, allow_nan_infinity: bool) -> Self {
+        self.allow_nan_infinity = allow_nan_infinity;
+        self
+    }
+
+    /// 允许数字以`+`开头, 例如`+1`.
+    pub fn allow_leading_plus(mut self, allow_leading_plus: bool) -> Self {
+        self.allow_leading_plus = allow_leading_plus;
+        self
+    }
+
+    /// 允许十六进制的整数, 例如`0x1F`.
+    pub fn allow_hex_numbers(mut self, allow_hex_numbers: bool) -> Self {
+        self.allow_hex_numbers = allow_hex_numbers;
+        self
+    }
+
+    /// 允许小数点的前面或者后面没有数字, 例如`.5`和`5.`.
+    pub fn allow_bare_decimal_points(mut self, allow_bare_decimal_points: bool) -> Self {
+        self.allow_bare_decimal_points = allow_bare_decimal_points;
+        self
+    }
+
+    /// 允许字符串中的`\`后面紧跟一个换行, 两者都不会出现在解码之后的字符串中.
+    pub fn allow_line_continuations(mut self, allow_line_continuations: bool) -> Self {
+        self.allow_line_continuations = allow_line_continuations;
+        self
+    }
+
+    /// 允许JSON5中的转义: `\v`, `\0`, `\xHH`, 以及把其他字符原样保留的转义, 例如`\q`就是`q`.
+    pub fn allow_extended_escapes(mut self, allow_extended_escapes: bool) -> Self {
+        self.allow_extended_escapes = allow_extended_escapes;
+        self
+    }
+
+    /// 除了空格, `\t`, `\n`和`\r`以外, 还把JSON5中的空白字符当作空白, 包括`\v`, `\f`, U+00A0, U+FEFF,
+    /// U+2028, U+2029以及其他的Unicode空格.
+    pub fn allow_extended_whitespace(mut self, allow_extended_whitespace: bool) -> Self {
+        self.allow_extended_whitespace = allow_extended_whitespace;
+        self
+    }
+
+    /// 数组和对象最多可以嵌套多少层.
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+
+    /// 输入最多可以有多少字节.
+    pub fn max_input_size(mut self, max_input_size: usize) -> Self {
+        self.max_input_size = max_input_size;
+        self
+    }
+
+    /// 解码之后的字符串(包括对象的键)最多可以有多少字节.
+    pub fn max_string_length(mut self, max_string_length: usize) -> Self {
+        self.max_string_length = max_string_length;
+        self
+    }
+
+    /// 单个数组或者对象最多可以有多少个元素.
+    pub fn max_elements(mut self, max_elements: usize) -> Self {
+        self.max_elements = max_elements;
+        self
+    }
+
+    /// 对象中出现重复的键时的处理方式, 默认是`DuplicateKeys::LastWins`.
+    pub fn duplicate_keys(mut self, duplicate_keys: DuplicateKeys) -> Self {
+        self.duplicate_keys = duplicate_keys;
+        self
+    }
+
+    /// 保留数字的原始文本, 而不是把它转换成`u64`, `i64`或者`f64`.
+    /// 这样任意长度, 任意精度的数字都可以原样地写回去.
+    pub fn arbitrary_precision(mut self, arbitrary_precision: bool) -> Self {
+        self.arbitrary_precision = arbitrary_precision;
+        self
+    }
+}
+
+/// JSON解析器, 在`EventReader`给出的事件之上构建`Json`.
+///
+/// 解析过程不使用递归: 尚未解析完的数组和对象保存在一个显式的栈上, 所以嵌套的深度只受`max_depth`和内存的限制,
+/// 与线程的栈大小无关.
 pub struct Parser<'a> {
-    tokenizer: Tokenizer<'a>,
+    reader: EventReader<'a>,
+    options: ParserOptions,
+}
+
+// 正在构建中的数组或者对象.
+enum Frame {
+    Array(Vec<Json>),
+    Object(Object),
+}
+
+struct Object {
+    map: Map,
+    // 已经读到, 还在等待值的键.
+    key: Option<(String, Span)>,
+    // 与`map`中的键值对一一对应: 键第一次出现的位置, 以及它的值是否已经被收集成了数组.
+    // 只有`DuplicateKeys::Error`和`DuplicateKeys::CollectAll`才需要.
+    seen: Vec<(Span, bool)>,
+}
+
+impl Object {
+    fn new() -> Self {
+        Self {
+            map: Map::new(),
+            key: None,
+            seen: Vec::new(),
+        }
+    }
+
+    // 按照`policy`插入一个键值对, 返回需要报告的重复键错误.
+    fn insert(
+        &mut self,
+        key: String,
+        span: Span,
+        value: Json,
+        policy: DuplicateKeys,
+    ) -> Option<JsonError> {
+        let tracked = policy == DuplicateKeys::Error || policy == DuplicateKeys::CollectAll;
+
+        let index = match self.map.index_of(&key) {
+            Some(index) => index,
+            None => {
+                if tracked {
+                    self.seen.push((span, false));
+                }
+                self.map.insert(key, value);
+                return None;
+            }
+        };
+
+        match policy {
+            DuplicateKeys::Error => Some(JsonError::DuplicateKey {
+                key,
+                first: self.seen[index].0,
+                second: span,
+            }),
+            DuplicateKeys::FirstWins => None,
+            DuplicateKeys::LastWins => {
+                self.map.insert(key, value);
+                None
+            }
+            DuplicateKeys::CollectAll => {
+                let (_, ref mut collected) = self.seen[index];
+                if let Some(entry) = self.map.get_mut(&key) {
+                    if !*collected {
+                        let first = entry.take();
+                        *entry = Json::Array(vec![first]);
+                        *collected = true;
+                    }
+                    if let Json::Array(ref mut values) = *entry {
+                        values.push(value);
+                    }
+                }
+                None
+            }
+        }
+    }
 }
 
 impl<'a> Parser<'a> {
     pub fn new(s: &'a str) -> Self {
+        Self::with_options(s, ParserOptions::default())
+    }
+
+    pub fn with_options(s: &'a str, options: ParserOptions) -> Self {
         Self {
-            tokenizer: Tokenizer::new(s),
+            reader: EventReader::with_options(s, options),
+            options,
         }
     }
 
-    pub fn parse(&mut self) -> Json {
-        let token = self.step();
+    /// 从`reader`中边读边解析, 不需要先把全部输入读进一个`String`.
+    pub fn from_reader<R: Read + 'a>(reader: R, options: ParserOptions) -> Self {
+        Self {
+            reader: EventReader::from_reader(reader, options),
+            options,
+        }
+    }
+
+    /// 解析一个完整的JSON文本, 值的后面除了空白字符以外不能再有其他内容.
+    pub fn parse(&mut self) -> Result<Json> {
+        let json = self.build()?;
+        self.parse_end()?;
 
-        self.parse_from(token)
+        json.ok_or_else(|| self.reader.eof_error())
     }
 
-    fn step(&mut self) -> Token {
-        self.tokenizer.next().expect("Unexpected end of JSON!!!")
+    /// 只解析输入开头的一个JSON值, 同时返回这个值所占的字节数, 剩余的内容不做处理.
+    pub fn parse_prefix(&mut self) -> Result<(Json, usize)> {
+        let json = self.build()?.ok_or_else(|| self.reader.eof_error())?;
+
+        Ok((json, self.reader.span().end.offset))
     }
 
-    fn parse_array(&mut self) -> Json {
-        let mut array = Vec::new();
+    /// 以恢复模式解析: 遇到语法错误时把它记录下来, 然后在`,`, `]`, `}`处重新同步并继续解析.
+    /// 返回尽可能完整的解析结果, 以及按出现顺序排列的所有错误, 没有错误时错误列表为空.
+    pub fn parse_recovering(&mut self) -> (Option<Json>, Vec<JsonError>) {
+        self.reader.set_recovering(true);
 
-        match self.step() {
-            Token::BracketOff => return array.into(),
-            token => array.push(self.parse_from(token)),
+        let result = self.build().and_then(|json| self.parse_end().map(|_| json));
+        let mut errors = self.reader.take_errors();
+
+        let json = match result {
+            Ok(json) => json,
+            Err(e) => {
+                errors.push(e);
+                None
+            }
+        };
+
+        (json, errors)
+    }
+
+    // 顶层的值读完之后, `EventReader`会检查后面是否还有多余的内容.
+    fn parse_end(&mut self) -> Result<()> {
+        match self.reader.next_event() {
+            Some(Err(e)) => Err(e),
+            _ => Ok(()),
         }
+    }
 
-        loop {
-            match self.step() {
-                Token::Comma => array.push(self.parse()),
-                Token::BracketOff => break,
-                token => panic!("Unexpected token {:?}", token),
+    // 读入事件直到顶层的值结束, 返回`None`表示没有得到有效的值, 只会在恢复模式下出现.
+    fn build(&mut self) -> Result<Option<Json>> {
+        let mut builder = Builder::new(self.options.duplicate_keys);
+
+        while let Some(event) = self.reader.next_event() {
+            if let Some(json) = builder.push(event?, &mut self.reader)? {
+                return Ok(Some(json));
             }
         }
 
-        array.into()
+        Ok(None)
     }
+}
+
+// 把事件逐个组装成`Json`, 尚未组装完的数组和对象保存在栈上.
+pub(crate) struct Builder {
+    stack: Vec<Frame>,
+    policy: DuplicateKeys,
+}
 
-    fn parse_object(&mut self) -> Json {
-        let mut object = HashMap::new();
+impl Builder {
+    pub(crate) fn new(policy: DuplicateKeys) -> Self {
+        Self {
+            stack: Vec::new(),
+            policy,
+        }
+    }
 
-        match self.step() {
-            Token::BraceOff => return object.into(),
-            Token::String(key) => {
-                match self.step() {
-                    Token::Colon => do_nothing(),
-                    token => panic!("Unexpected token {:?}", token),
+    // 加入`reader`刚刚给出的事件, 顶层的值组装完成时返回这个值.
+    pub(crate) fn push(&mut self, event: Event, reader: &mut EventReader) -> Result<Option<Json>> {
+        let value = match event {
+            Event::StartArray => {
+                self.stack.push(Frame::Array(Vec::new()));
+                return Ok(None);
+            }
+            Event::StartObject => {
+                self.stack.push(Frame::Object(Object::new()));
+                return Ok(None);
+            }
+            Event::Key(key) => {
+                if let Some(Frame::Object(ref mut object)) = self.stack.last_mut() {
+                    object.key = Some((key, reader.span()));
                 }
-                let value = self.parse();
-                object.insert(key, value);
+                return Ok(None);
             }
-            token => panic!("Unexpected token {:?}", token),
-        }
+            Event::Scalar(value) => value,
+            Event::EndArray | Event::EndObject => match self.stack.pop() {
+                Some(Frame::Array(array)) => Json::Array(array),
+                Some(Frame::Object(object)) => Json::Object(object.map),
+                None => return Ok(None),
+            },
+        };
 
-        loop {
-            match self.step() {
-                Token::Comma => {
-                    let key = match self.step() {
-                        Token::String(key) => key,
-                        token => panic!("Unexpected token {:?}", token),
-                    };
-                    match self.step() {
-                        Token::Colon => {}
-                        token => panic!("Unexpected token {:?}", token),
+        match self.stack.last_mut() {
+            None => return Ok(Some(value)),
+            Some(Frame::Array(ref mut array)) => array.push(value),
+            Some(Frame::Object(ref mut object)) => {
+                let key = object.key.take().filter(|_| reader.has_key());
+                if let Some((key, span)) = key {
+                    if let Some(error) = object.insert(key, span, value, self.policy) {
+                        reader.report(error)?;
                     }
-                    let value = self.parse();
-                    object.insert(key, value);
                 }
-                Token::BraceOff => break,
-                token => panic!("Unexpected token {:?}", token),
             }
         }
 
-        object.into()
-    }
-
-    fn parse_from(&mut self, token: Token) -> Json {
-        match token {
-            Token::Null => Json::Null,
-            Token::String(s) => Json::String(s),
-            Token::Number(n) => Json::Number(n),
-            Token::Boolean(b) => Json::Boolean(b),
-            Token::BracketOn => self.parse_array(),
-            Token::BraceOn => self.parse_object(),
-            _ => panic!("Unexpected token: {:?}", token),
-        }
+        Ok(None)
     }
 }
@@ -0,0 +1,281 @@
+use std::str;
+
+use crate::error::JsonError;
+use crate::event_reader::{Event, EventReader};
+use crate::parser::{Builder, ParserOptions};
+use crate::position::{Position, Span};
+use crate::token::SpannedToken;
+use crate::tokenizer::{is_line_terminator, is_whitespace, Tokenizer};
+use crate::value::Json;
+use crate::Result;
+
+/// `PushParser`每次读取的结果.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Incremental<T> {
+    /// 读到了一个完整的事件或者值.
+    Ready(T),
+    /// 已有的输入还不够, 需要继续调用`feed`, 或者调用`finish`表示输入已经结束.
+    NeedMoreInput,
+    /// 文档已经读完.
+    End,
+}
+
+/// 推送式的增量解析器, 输入可以分成任意多块陆续提供, 例如从网络上陆续收到的数据.
+///
+/// 块的边界可以落在任何地方, 包括token的中间, 一个UTF-8字符的中间, 以及`\uXXXX`转义的中间.
+/// 能够确定的事件会立即给出; 输入不够时返回`Incremental::NeedMoreInput`, 而不是报告输入意外结束,
+/// 只有调用了`finish`之后, 输入的末尾才会被当作文档的结尾.
+///
+/// ```text
+/// feed(b"[1, tr")  -> StartArray, Scalar(1), NeedMoreInput
+/// feed(b"ue]")     -> Scalar(true), EndArray, NeedMoreInput
+/// finish()         -> End
+/// ```
+///
+/// 遇到错误之后解析就结束了, 之后总是返回`Incremental::End`.
+pub struct PushParser {
+    reader: EventReader<'static>,
+    builder: Builder,
+}
+
+impl PushParser {
+    pub fn new() -> Self {
+        Self::with_options(ParserOptions::default())
+    }
+
+    pub fn with_options(options: ParserOptions) -> Self {
+        Self {
+            reader: EventReader::push(options),
+            builder: Builder::new(options.duplicate_keys),
+        }
+    }
+
+    /// 提供下一块输入. 只有遇到无效的UTF-8编码时才会返回错误.
+    pub fn feed(&mut self, bytes: &[u8]) -> Result<()> {
+        self.reader.buffer().push(bytes)
+    }
+
+    /// 表示输入已经全部提供. 输入末尾有不完整的UTF-8字符时返回错误.
+    pub fn finish(&mut self) -> Result<()> {
+        self.reader.buffer().close()
+    }
+
+    /// 读取下一个事件.
+    pub fn next_event(&mut self) -> Result<Incremental<Event>> {
+        match self.reader.next_event() {
+            Some(Ok(event)) => Ok(Incremental::Ready(event)),
+            Some(Err(e)) => Err(e),
+            None if self.reader.is_suspended() => Ok(Incremental::NeedMoreInput),
+            None => Ok(Incremental::End),
+        }
+    }
+
+    /// 读取顶层的值. 值一读完就会给出, 之后的调用才会检查值的后面是否有多余的内容.
+    ///
+    /// 同一个`PushParser`上不要混用`next_event`和`next_value`.
+    pub fn next_value(&mut self) -> Result<Incremental<Json>> {
+        loop {
+            let event = match self.next_event()? {
+                Incremental::Ready(event) => event,
+                Incremental::NeedMoreInput => return Ok(Incremental::NeedMoreInput),
+                Incremental::End => return Ok(Incremental::End),
+            };
+
+            if let Some(json) = self.builder.push(event, &mut self.reader)? {
+                return Ok(Incremental::Ready(json));
+            }
+        }
+    }
+
+    /// 最近一个事件所属的值在文档中的位置, 参见`EventReader::pointer`.
+    pub fn pointer(&self) -> String {
+        self.reader.pointer()
+    }
+
+    /// 最近读入的token在完整输入中的区间.
+    pub fn span(&self) -> Span {
+        self.reader.span()
+    }
+}
+
+impl Default for PushParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// 保存已经收到但还没有读完的输入.
+pub(crate) struct PushBuffer {
+    text: String,
+    // `text`中已经读完的字节数.
+    consumed: usize,
+    // `text[consumed..]`在完整输入中的起始位置.
+    position: Position,
+    // 末尾还不完整的UTF-8字符.
+    partial: Vec<u8>,
+    // 下一个token已经扫描到了`text[..scanned]`, 以及扫描到那里时的状态.
+    scanned: usize,
+    scan: Scan,
+    closed: bool,
+    options: ParserOptions,
+}
+
+// 扫描下一个token时的状态, 只用来找出token在哪里结束, token的内容由`Tokenizer`负责检查.
+#[derive(Clone, Copy)]
+enum Scan {
+    // 空白字符和注释, 还没有遇到token.
+    Trivia,
+    // 注释开头的`/`之后.
+    Slash,
+    LineComment,
+    // 上一个字符是否是`*`.
+    BlockComment(bool),
+    // 引号, 以及上一个字符是否是转义用的`\`.
+    String(char, bool),
+    // 数字, `true`, `false`, `null`以及标识符.
+    Word,
+}
+
+impl PushBuffer {
+    pub(crate) fn new(options: ParserOptions) -> Self {
+        Self {
+            text: String::new(),
+            consumed: 0,
+            position: Position::start(),
+            partial: Vec::new(),
+            scanned: 0,
+            scan: Scan::Trivia,
+            closed: false,
+            options,
+        }
+    }
+
+    pub(crate) fn position(&self) -> Position {
+        self.position
+    }
+
+    pub(crate) fn push(&mut self, bytes: &[u8]) -> Result<()> {
+        self.text.drain(..self.consumed);
+        self.scanned -= self.consumed;
+        self.consumed = 0;
+        self.partial.extend_from_slice(bytes);
+
+        let valid = match str::from_utf8(&self.partial) {
+            Ok(s) => s.len(),
+            // 最后一个字符还不完整, 留到下一块.
+            Err(e) if e.error_len().is_none() => e.valid_up_to(),
+            Err(e) => {
+                let valid = str::from_utf8(&self.partial[..e.valid_up_to()])
+                    .expect("the prefix is valid UTF-8");
+                let mut position = self.end();
+                valid.chars().for_each(|ch| position.advance(ch));
+                return Err(JsonError::InvalidUtf8 { position });
+            }
+        };
+
+        let text = str::from_utf8(&self.partial[..valid]).expect("the prefix is valid UTF-8");
+        self.text.push_str(text);
+        self.partial.drain(..valid);
+
+        Ok(())
+    }
+
+    pub(crate) fn close(&mut self) -> Result<()> {
+        self.closed = true;
+
+        if self.partial.is_empty() {
+            Ok(())
+        } else {
+            Err(JsonError::InvalidUtf8 {
+                position: self.end(),
+            })
+        }
+    }
+
+    // 读取下一个token, 外层的`None`表示已有的输入还不足以确定下一个token.
+    // 先增量地扫描出token的结尾, 再交给`Tokenizer`, 这样分成多少块, 每个字符都只会被扫描和解析一次.
+    pub(crate) fn next_spanned(&mut self) -> Option<Option<Result<SpannedToken>>> {
+        if !self.closed && !self.scan() {
+            return None;
+        }
+
+        let rest = &self.text[self.consumed..];
+        let mut tokenizer = Tokenizer::starting_at(rest, self.options, self.position);
+        let token = tokenizer.next_spanned();
+        let end = match token {
+            Some(Ok(ref token)) => token.span.end,
+            _ => tokenizer.position(),
+        };
+
+        self.consumed += end.offset - self.position.offset;
+        self.position = end;
+        self.scanned = self.consumed;
+        self.scan = Scan::Trivia;
+
+        Some(token)
+    }
+
+    // 从上次停下的地方继续扫描, 返回下一个token是否已经完整, 也就是后面的输入不会再改变它.
+    fn scan(&mut self) -> bool {
+        let options = self.options;
+        let mut scan = self.scan;
+
+        for (index, ch) in self.text[self.scanned..].char_indices() {
+            // 超出上限时交给`Tokenizer`报告错误, 不再继续缓存输入.
+            let offset = self.position.offset + self.scanned + index - self.consumed;
+            if offset > options.max_input_size {
+                return true;
+            }
+
+            match scan {
+                Scan::Trivia if is_whitespace(ch, options.allow_extended_whitespace) => {}
+                Scan::Trivia if ch == '/' && options.allow_comments => scan = Scan::Slash,
+                Scan::Trivia if ch == '"' || ch == '\'' && options.allow_single_quotes => {
+                    scan = Scan::String(ch, false)
+                }
+                Scan::Trivia if is_word(ch) => scan = Scan::Word,
+                // 标点符号, 或者一个无效的字符.
+                Scan::Trivia => return true,
+                Scan::Slash if ch == '/' => scan = Scan::LineComment,
+                Scan::Slash if ch == '*' => scan = Scan::BlockComment(false),
+                Scan::Slash => return true,
+                Scan::LineComment if is_line_terminator(ch) => {
+                    if options.emit_comments {
+                        return true;
+                    }
+                    scan = Scan::Trivia;
+                }
+                Scan::LineComment => {}
+                Scan::BlockComment(true) if ch == '/' => {
+                    if options.emit_comments {
+                        return true;
+                    }
+                    scan = Scan::Trivia;
+                }
+                Scan::BlockComment(_) => scan = Scan::BlockComment(ch == '*'),
+                Scan::String(quote, false) if ch == quote => return true,
+                Scan::String(quote, escaped) => scan = Scan::String(quote, !escaped && ch == '\\'),
+                Scan::Word if is_word(ch) => {}
+                Scan::Word => return true,
+            }
+        }
+
+        self.scanned = self.text.len();
+        self.scan = scan;
+        false
+    }
+
+    // 已经收到的完整字符之后的位置.
+    fn end(&self) -> Position {
+        let mut position = self.position;
+        self.text[self.consumed..]
+            .chars()
+            .for_each(|ch| position.advance(ch));
+        position
+    }
+}
+
+// 可能出现在数字, 字面量和标识符中的字符.
+fn is_word(ch: char) -> bool {
+    ch.is_alphanumeric() || matches!(ch, '+' | '-' | '.' | '_' | '$')
+}
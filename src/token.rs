@@ -1,6 +1,13 @@
+use std::fmt;
+
+use crate::number::Number;
+use crate::position::Span;
+
 /// 对于Json的token有：
-/// `,`, `:`, `{`, `}`, `[`, `]`, `String`, `Number`, `Boolean`, `Null`
-#[derive(Debug, PartialEq)]
+/// `,`, `:`, `{`, `}`, `[`, `]`, `String`, `Number`, `Boolean`, `Null`,
+/// 以及打开`ParserOptions::allow_unquoted_keys`之后才会出现的`Identifier`(这时`Infinity`和`NaN`也是`Identifier`),
+/// 打开`ParserOptions::emit_comments`之后才会出现的`LineComment`和`BlockComment`.
+#[derive(Debug, Clone, PartialEq)]
 pub enum Token {
     Comma,
     Colon,
@@ -9,7 +16,50 @@ pub enum Token {
     BraceOn,
     BraceOff,
     String(String),
-    Number(f64),
+    Number(Number),
     Boolean(bool),
     Null,
+    Identifier(String),
+    LineComment(String),
+    BlockComment(String),
+}
+
+impl Token {
+    /// 注释不影响JSON的值, 解析时总是被忽略.
+    pub fn is_trivia(&self) -> bool {
+        matches!(*self, Token::LineComment(_) | Token::BlockComment(_))
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Token::Comma => f.write_str("`,`"),
+            Token::Colon => f.write_str("`:`"),
+            Token::BracketOn => f.write_str("`[`"),
+            Token::BracketOff => f.write_str("`]`"),
+            Token::BraceOn => f.write_str("`{`"),
+            Token::BraceOff => f.write_str("`}`"),
+            Token::String(ref s) => write!(f, "string {:?}", s),
+            Token::Number(ref n) => write!(f, "number `{}`", n),
+            Token::Boolean(b) => write!(f, "`{}`", b),
+            Token::Null => f.write_str("`null`"),
+            Token::Identifier(ref name) => write!(f, "identifier `{}`", name),
+            Token::LineComment(ref text) => write!(f, "comment `//{}`", text),
+            Token::BlockComment(ref text) => write!(f, "comment `/*{}*/`", text),
+        }
+    }
+}
+
+/// 带有位置信息的token.
+#[derive(Debug, PartialEq)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
+
+impl SpannedToken {
+    pub fn new(token: Token, span: Span) -> Self {
+        Self { token, span }
+    }
 }
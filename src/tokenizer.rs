@@ -1,136 +1,591 @@
+use std::io::Read;
 use std::iter::Peekable;
 use std::str::Chars;
 
-use crate::token::Token;
+use crate::error::{JsonError, Limit};
+use crate::io::ReadChars;
+use crate::number::Number;
+use crate::parser::ParserOptions;
+use crate::position::{Position, Span};
+use crate::token::{SpannedToken, Token};
+use crate::Result;
 
 pub struct Tokenizer<'a> {
-    source: Peekable<Chars<'a>>,
+    source: Source<'a>,
+    position: Position,
+    start: Position,
+    options: ParserOptions,
+}
+
+// 输入可以是一个字符串, 也可以是一个`Read`.
+enum Source<'a> {
+    Str(Peekable<Chars<'a>>),
+    Reader(ReadChars<'a>),
 }
 
 impl<'a> Tokenizer<'a> {
     pub fn new(s: &'a str) -> Self {
+        Self::with_options(s, ParserOptions::default())
+    }
+
+    pub fn with_options(s: &'a str, options: ParserOptions) -> Self {
+        Self::starting_at(s, options, Position::start())
+    }
+
+    /// 从`reader`中边读边解析, 输入必须是UTF-8编码的.
+    /// 读取失败时给出`JsonError::Io`, 遇到无效的UTF-8编码时给出`JsonError::InvalidUtf8`.
+    pub fn from_reader<R: Read + 'a>(reader: R, options: ParserOptions) -> Self {
+        Self {
+            source: Source::Reader(ReadChars::new(reader)),
+            position: Position::start(),
+            start: Position::start(),
+            options,
+        }
+    }
+
+    // `s`是完整输入中从`position`开始的一段, 给出的位置都相对于完整的输入.
+    pub(crate) fn starting_at(s: &'a str, options: ParserOptions, position: Position) -> Self {
         Self {
-            source: s.chars().peekable(),
+            source: Source::Str(s.chars().peekable()),
+            position,
+            start: position,
+            options,
         }
     }
 
+    /// 当前读到的位置.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+
+    /// 读取下一个token, 同时给出它在输入中的区间.
+    pub fn next_spanned(&mut self) -> Option<Result<SpannedToken>> {
+        let token = self.lex()?;
+        Some(token.map(|token| SpannedToken::new(token, Span::new(self.start, self.position))))
+    }
+
+    // 读取输入出错时, 之前看到的输入是被截断的, 据此得到的token没有意义, 所以给出读取的错误.
+    fn lex(&mut self) -> Option<Result<Token>> {
+        let token = self.read_token();
+
+        let position = self.position;
+        match self.source {
+            Source::Reader(ref mut reader) => match reader.take_error(position) {
+                Some(error) => Some(Err(error)),
+                None => token,
+            },
+            Source::Str(_) => token,
+        }
+    }
+
+    fn peek(&mut self) -> Option<char> {
+        match self.source {
+            Source::Str(ref mut chars) => chars.peek().copied(),
+            Source::Reader(ref mut reader) => reader.peek(),
+        }
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let ch = match self.source {
+            Source::Str(ref mut chars) => chars.next(),
+            Source::Reader(ref mut reader) => reader.next(),
+        }?;
+        self.position.advance(ch);
+        Some(ch)
+    }
+
     // 对于null, true, false这样的符号, 只需要一步一步的向后便利判断就可以了.
+    // 允许不带引号的键时, 符号也可以是一个标识符, 其中可以有`_`, `$`以及非ASCII的字母和数字.
     fn read_symbol(&mut self, first: char) -> String {
         let mut symbol = first.to_string();
 
-        while let Some(&ch) = self.source.peek() {
-            match ch {
-                'a'..='z' => {
-                    symbol.push(ch);
-                    self.source.next();
-                }
-                _ => break, // 遇到非英文小写字母，判定它结束
+        while let Some(ch) = self.peek() {
+            if ch.is_ascii_alphanumeric()
+                || (self.options.allow_unquoted_keys && is_identifier_part(ch))
+            {
+                symbol.push(ch);
+                self.bump();
+            } else {
+                break; // 遇到非英文字母或数字，判定它结束
             }
         }
 
         symbol
     }
 
-    // "xxx 12.345 xxx"
-    //      ^^^^^^
-    //        |
-    //     这里是数字
-    // 可能带有小数点,也可能不带有小数点,
-    // 那么,我们可以设立一个游标cursor,去一步一步的前进(cursor++),
-    // 如果如果是字符数字,那么保存这个数字,并且向下一步前进.
-    // 当遇到小数点的时候,判断这个小数点是否是第一次出现, 如果是第一次出现,
-    // 那没什么问题,如果是第二次出现及以上,那么说明这个数字字符串是无效非法的.
-    fn read_number(&mut self, first: char) -> f64 {
+    // "xxx -12.345e+6 xxx"
+    //      ^^^^^^^^^^^
+    //           |
+    //       这里是数字
+    // JSON的数字由四部分组成: 可选的负号, 整数部分, 可选的小数部分, 可选的指数部分.
+    //
+    //   number = [ "-" ] int [ frac ] [ exp ]
+    //   int    = "0" / ( digit1-9 *digit )
+    //   frac   = "." 1*digit
+    //   exp    = ( "e" / "E" ) [ "-" / "+" ] 1*digit
+    //
+    // 整数部分不允许有前导零, 小数点和指数符号后面都至少要有一位数字.
+    // 在arbitrary_precision模式下, 校验通过的原始文本会被原样保留下来.
+    //
+    // 根据选项, 还可以接受开头的`+`, 十六进制的整数, `Infinity`和`NaN`, 以及`.5`和`5.`这样的小数.
+    fn read_number(&mut self, first: char) -> Result<Number> {
         let mut value = first.to_string();
-        let mut point = false;
+        let bare = self.options.allow_bare_decimal_points;
 
-        while let Some(&ch) = self.source.peek() {
-            match ch {
-                '0'..='9' | '-' => {
+        // 整数部分的第一位数字, 没有整数部分时为`None`.
+        let leading = match first {
+            '-' | '+' => match self.peek() {
+                Some(ch @ '0'..='9') => {
+                    self.bump();
                     value.push(ch);
-                    self.source.next();
+                    Some(ch)
                 }
-                '.' => {
-                    if !point {
-                        point = true;
-                        value.push(ch);
-                        self.source.next();
-                    } else {
-                        return value.parse::<f64>().unwrap();
-                    }
+                Some('.') if bare => None,
+                Some('I') | Some('N') if self.options.allow_nan_infinity => {
+                    return self.read_non_finite(value)
                 }
-                _ => return value.parse::<f64>().unwrap(),
+                _ => return Err(self.invalid_number(value)),
+            },
+            '.' => None,
+            _ => Some(first),
+        };
+
+        match leading {
+            Some('0') => match self.peek() {
+                Some(ch @ '0'..='9') => {
+                    self.bump();
+                    value.push(ch);
+                    return Err(self.invalid_number(value));
+                }
+                Some('x') | Some('X') if self.options.allow_hex_numbers => {
+                    return self.read_hex_number(value)
+                }
+                _ => {}
+            },
+            Some(_) => {
+                self.read_digits(&mut value);
+            }
+            None => {}
+        }
+
+        let point = first == '.' || self.peek() == Some('.');
+        if point {
+            if first != '.' {
+                self.bump();
+                value.push('.');
+            }
+            if self.read_digits(&mut value) == 0 && (leading.is_none() || !bare) {
+                return Err(self.invalid_number(value));
+            }
+        }
+
+        if let Some(ch @ 'e') | Some(ch @ 'E') = self.peek() {
+            self.bump();
+            value.push(ch);
+            if let Some(sign @ '+') | Some(sign @ '-') = self.peek() {
+                self.bump();
+                value.push(sign);
+            }
+            if self.read_digits(&mut value) == 0 {
+                return Err(self.invalid_number(value));
             }
         }
 
-        value.parse::<f64>().unwrap()
+        self.finish_number(value)
+    }
+
+    // 开头的`+`不会保留在数字的文本中, `.5`和`5.`会被补全成`0.5`和`5.0`.
+    fn finish_number(&self, value: String) -> Result<Number> {
+        let mut text = value.strip_prefix('+').unwrap_or(&value).to_owned();
+
+        if let Some(point) = text.find('.') {
+            if !text[point + 1..].starts_with(|ch: char| ch.is_ascii_digit()) {
+                text.insert(point + 1, '0');
+            }
+            if !text[..point].ends_with(|ch: char| ch.is_ascii_digit()) {
+                text.insert(point, '0');
+            }
+        }
+
+        if self.options.arbitrary_precision {
+            return Ok(Number::from_raw(text));
+        }
+
+        match Number::from_lexeme(&text) {
+            Some(number) => Ok(number),
+            None => Err(self.invalid_number(value)),
+        }
+    }
+
+    // 0x1F, -0xff
+    // 十六进制的整数会被转换成十进制的文本, 之后与普通的数字一样处理.
+    fn read_hex_number(&mut self, mut value: String) -> Result<Number> {
+        if let Some(x) = self.bump() {
+            value.push(x);
+        }
+
+        let digits = value.len();
+        while let Some(ch) = self.peek() {
+            if !ch.is_ascii_hexdigit() {
+                break;
+            }
+            self.bump();
+            value.push(ch);
+        }
+
+        match u64::from_str_radix(&value[digits..], 16) {
+            Ok(n) if value.starts_with('-') => self.finish_number(format!("-{}", n)),
+            Ok(n) => self.finish_number(n.to_string()),
+            Err(_) => Err(self.invalid_number(value)),
+        }
+    }
+
+    // Infinity, -Infinity, NaN
+    fn read_non_finite(&mut self, sign: String) -> Result<Number> {
+        let first = self.bump().expect("the caller has peeked a character");
+        let symbol = self.read_symbol(first);
+
+        let f = match symbol.as_ref() {
+            "Infinity" => f64::INFINITY,
+            "NaN" => f64::NAN,
+            _ => return Err(self.invalid_number(sign + &symbol)),
+        };
+
+        Ok(Number::from_f64(if sign == "-" { -f } else { f }))
+    }
+
+    fn read_digits(&mut self, value: &mut String) -> usize {
+        let mut count = 0;
+
+        while let Some(ch @ '0'..='9') = self.peek() {
+            self.bump();
+            value.push(ch);
+            count += 1;
+        }
+
+        count
+    }
+
+    fn invalid_number(&self, number: String) -> JsonError {
+        JsonError::InvalidNumber {
+            number,
+            position: self.start,
+        }
     }
 
     // r#" "this is a string" "
     //     ^^^^^^^^^^^^^^^^^^
     // 对于字符串来说,以双引号开头,以双引号结尾,同时,字符串中可能会有'\'反斜杠开头的转义字符.
-    //
-    fn read_string(&mut self, first: char) -> String {
+    // 允许单引号时, 字符串也可以以单引号开头, 以单引号结尾.
+    // 允许续行时, 紧跟在`\\`后面的换行会被忽略, 这样一个字符串可以写成多行.
+    // 未经转义的控制字符(U+0000到U+001F)是不允许出现在字符串中的.
+    fn read_string(&mut self, first: char) -> Result<String> {
         let mut value = String::new();
-        let mut escape = false;
-
-        while let Some(ch) = self.source.next() {
-            if ch == first && !escape {
-                return value;
-            }
-            match ch {
-                '\\' => {
-                    if escape {
-                        escape = false;
-                        value.push(ch);
-                    } else {
-                        escape = true;
-                    }
+
+        loop {
+            if value.len() > self.options.max_string_length {
+                return Err(self.limit_exceeded(Limit::StringLength));
+            }
+
+            let position = self.position;
+            match self.bump() {
+                Some(ch) if ch == first => return Ok(value),
+                Some('\\')
+                    if self.options.allow_line_continuations && self.skip_line_terminator() => {}
+                Some('\\') => value.push(self.read_escape(position)?),
+                Some(ch @ '\u{0}'..='\u{1f}') => {
+                    return Err(JsonError::ControlCharacter { ch, position })
                 }
-                _ => {
-                    value.push(ch);
-                    escape = false;
+                Some(ch) => value.push(ch),
+                None => return Err(self.unterminated_string()),
+            }
+        }
+    }
+
+    // 换行可以是\n, \r\n, \r, U+2028或者U+2029.
+    fn skip_line_terminator(&mut self) -> bool {
+        match self.peek() {
+            Some('\r') => {
+                self.bump();
+                if self.peek() == Some('\n') {
+                    self.bump();
                 }
+                true
+            }
+            Some(ch) if is_line_terminator(ch) => {
+                self.bump();
+                true
             }
+            _ => false,
         }
+    }
 
-        value
+    // 转义字符有: \" \\ \/ \b \f \n \r \t 以及 \uXXXX, 允许单引号时还有 \'.
+    // 允许JSON5的转义时, 还有 \v \0 \xHH, 其余除了数字以外的字符都表示它自己.
+    fn read_escape(&mut self, start: Position) -> Result<char> {
+        let ch = match self.bump() {
+            Some(ch) => ch,
+            None => return Err(self.unterminated_string()),
+        };
+
+        Ok(match ch {
+            '"' => '"',
+            '\'' if self.options.allow_single_quotes => '\'',
+            '\\' => '\\',
+            '/' => '/',
+            'b' => '\u{8}',
+            'f' => '\u{c}',
+            'n' => '\n',
+            'r' => '\r',
+            't' => '\t',
+            'u' => return self.read_unicode_escape(start),
+            'v' if self.options.allow_extended_escapes => '\u{b}',
+            '0' if self.options.allow_extended_escapes
+                && !matches!(self.peek(), Some('0'..='9')) =>
+            {
+                '\u{0}'
+            }
+            'x' if self.options.allow_extended_escapes => {
+                let code = self.read_hex(2, start).map_err(|e| match e {
+                    JsonError::InvalidUnicodeEscape { .. } => JsonError::InvalidEscape {
+                        ch,
+                        position: start,
+                    },
+                    e => e,
+                })?;
+                char::from_u32(code).expect("two hex digits are always a valid char")
+            }
+            _ if self.options.allow_extended_escapes && !ch.is_ascii_digit() => ch,
+            _ => {
+                return Err(JsonError::InvalidEscape {
+                    ch,
+                    position: start,
+                })
+            }
+        })
     }
-}
 
-impl<'a> Iterator for Tokenizer<'a> {
-    type Item = Token;
+    // \uXXXX只能表示基本多文种平面(BMP)内的字符, 其余的字符需要用UTF-16的代理对来表示:
+    // 高代理(U+D800到U+DBFF)后面必须紧跟一个低代理(U+DC00到U+DFFF), 例如 "\uD83D\uDE00" 是 😀.
+    fn read_unicode_escape(&mut self, start: Position) -> Result<char> {
+        let high = self.read_hex4(start)?;
 
-    fn next(&mut self) -> Option<Self::Item> {
-        'lex: while let Some(ch) = self.source.next() {
-            return Some(match ch {
-                ',' => Token::Comma,
-                ':' => Token::Colon,
-                '[' => Token::BracketOn,
-                ']' => Token::BracketOff,
-                '{' => Token::BraceOn,
-                '}' => Token::BraceOff,
-                '"' => Token::String(self.read_string(ch)),
-                '0'..='9' | '-' => Token::Number(self.read_number(ch)),
-                'a'..='z' => {
-                    let label = self.read_symbol(ch);
-                    match label.as_ref() {
-                        "true" => Token::Boolean(true),
-                        "false" => Token::Boolean(false),
-                        "null" => Token::Null,
-                        _ => panic!("Invalid label: {}", label),
+        let code = match high {
+            0xD800..=0xDBFF => {
+                let low_start = self.position;
+                if self.peek() != Some('\\') {
+                    return Err(JsonError::LoneSurrogate { position: start });
+                }
+                self.bump();
+                if self.bump() != Some('u') {
+                    return Err(JsonError::LoneSurrogate { position: start });
+                }
+                let low = self.read_hex4(low_start)?;
+                if !(0xDC00..=0xDFFF).contains(&low) {
+                    return Err(JsonError::LoneSurrogate { position: start });
+                }
+                0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+            }
+            0xDC00..=0xDFFF => return Err(JsonError::LoneSurrogate { position: start }),
+            _ => high,
+        };
+
+        Ok(char::from_u32(code).expect("surrogates have been handled above"))
+    }
+
+    fn read_hex4(&mut self, start: Position) -> Result<u32> {
+        self.read_hex(4, start)
+    }
+
+    fn read_hex(&mut self, count: usize, start: Position) -> Result<u32> {
+        let mut code = 0;
+
+        for _ in 0..count {
+            let ch = match self.bump() {
+                Some(ch) => ch,
+                None => return Err(self.unterminated_string()),
+            };
+            match ch.to_digit(16) {
+                Some(digit) => code = code * 16 + digit,
+                None => return Err(JsonError::InvalidUnicodeEscape { position: start }),
+            }
+        }
+
+        Ok(code)
+    }
+
+    fn unterminated_string(&self) -> JsonError {
+        JsonError::UnterminatedString {
+            position: self.start,
+        }
+    }
+
+    fn limit_exceeded(&self, limit: Limit) -> JsonError {
+        JsonError::LimitExceeded {
+            limit,
+            position: self.start,
+        }
+    }
+
+    // `// ...`一直到行尾, `/* ... */`可以跨越多行, 但是不能嵌套.
+    // 调用之前第一个`/`已经被读掉了, 注释的内容不包括`//`, `/*`和`*/`以及行尾的换行.
+    fn read_comment(&mut self) -> Result<Token> {
+        let mut text = String::new();
+
+        match self.peek() {
+            Some('/') => {
+                self.bump();
+                while let Some(ch) = self.peek() {
+                    if is_line_terminator(ch) {
+                        break;
                     }
+                    self.bump();
+                    text.push(ch);
                 }
-                _ => {
-                    if ch.is_whitespace() {
-                        continue 'lex;
-                    } else {
-                        panic!("Invalid character: {}", ch);
+                Ok(Token::LineComment(text))
+            }
+            Some('*') => {
+                self.bump();
+                loop {
+                    match self.bump() {
+                        Some('/') if text.ends_with('*') => {
+                            text.pop();
+                            return Ok(Token::BlockComment(text));
+                        }
+                        Some(ch) => text.push(ch),
+                        None => {
+                            return Err(JsonError::UnterminatedComment {
+                                position: self.start,
+                            })
+                        }
                     }
                 }
-            });
+            }
+            _ => Err(JsonError::UnexpectedCharacter {
+                ch: '/',
+                position: self.start,
+            }),
+        }
+    }
+
+    fn read_token(&mut self) -> Option<Result<Token>> {
+        while let Some(ch) = self.peek() {
+            if self.position.offset > self.options.max_input_size {
+                break;
+            }
+            if is_whitespace(ch, self.options.allow_extended_whitespace) {
+                self.bump();
+            } else if ch == '/' && self.options.allow_comments && !self.options.emit_comments {
+                self.start = self.position;
+                self.bump();
+                if let Err(e) = self.read_comment() {
+                    return Some(Err(e));
+                }
+            } else {
+                break;
+            }
+        }
+
+        self.start = self.position;
+        if self.position.offset > self.options.max_input_size {
+            return Some(Err(self.limit_exceeded(Limit::InputSize)));
         }
 
-        None
+        let ch = self.bump()?;
+        let token = self.read_token_from(ch);
+
+        // 超长的token也不能放过.
+        if self.position.offset > self.options.max_input_size {
+            return Some(Err(self.limit_exceeded(Limit::InputSize)));
+        }
+
+        Some(token)
+    }
+
+    fn read_token_from(&mut self, ch: char) -> Result<Token> {
+        match ch {
+            ',' => Ok(Token::Comma),
+            ':' => Ok(Token::Colon),
+            '[' => Ok(Token::BracketOn),
+            ']' => Ok(Token::BracketOff),
+            '{' => Ok(Token::BraceOn),
+            '}' => Ok(Token::BraceOff),
+            '"' => self.read_string(ch).map(Token::String),
+            '\'' if self.options.allow_single_quotes => self.read_string(ch).map(Token::String),
+            '0'..='9' | '-' => self.read_number(ch).map(Token::Number),
+            '.' if self.options.allow_bare_decimal_points => {
+                self.read_number(ch).map(Token::Number)
+            }
+            '+' if self.options.allow_leading_plus => self.read_number(ch).map(Token::Number),
+            '/' if self.options.allow_comments => self.read_comment(),
+            'a'..='z' | 'A'..='Z' => {
+                let label = self.read_symbol(ch);
+                match label.as_ref() {
+                    "true" => Ok(Token::Boolean(true)),
+                    "false" => Ok(Token::Boolean(false)),
+                    "null" => Ok(Token::Null),
+                    // 允许不带引号的键时, `Infinity`和`NaN`也可以作为键, 所以保留标识符的原文,
+                    // 由`EventReader`根据所在的位置决定它是键还是数字.
+                    _ if self.options.allow_unquoted_keys => Ok(Token::Identifier(label)),
+                    "Infinity" if self.options.allow_nan_infinity => {
+                        Ok(Token::Number(Number::from_f64(f64::INFINITY)))
+                    }
+                    "NaN" if self.options.allow_nan_infinity => {
+                        Ok(Token::Number(Number::from_f64(f64::NAN)))
+                    }
+                    _ => Err(JsonError::InvalidLiteral {
+                        literal: label,
+                        position: self.start,
+                    }),
+                }
+            }
+            _ if self.options.allow_unquoted_keys && is_identifier_start(ch) => {
+                Ok(Token::Identifier(self.read_symbol(ch)))
+            }
+            _ => Err(JsonError::UnexpectedCharacter {
+                ch,
+                position: self.start,
+            }),
+        }
+    }
+}
+
+// 结束单行注释的换行符, 也用于字符串中的续行.
+pub(crate) fn is_line_terminator(ch: char) -> bool {
+    matches!(ch, '\n' | '\r' | '\u{2028}' | '\u{2029}')
+}
+
+// RFC 8259只允许空格, `\t`, `\n`和`\r`; JSON5还允许其他的Unicode空白字符.
+pub(crate) fn is_whitespace(ch: char, extended: bool) -> bool {
+    match ch {
+        ' ' | '\t' | '\n' | '\r' => true,
+        '\u{b}'
+        | '\u{c}'
+        | '\u{a0}'
+        | '\u{1680}'
+        | '\u{2000}'..='\u{200a}'
+        | '\u{2028}'
+        | '\u{2029}'
+        | '\u{202f}'
+        | '\u{205f}'
+        | '\u{3000}'
+        | '\u{feff}' => extended,
+        _ => false,
+    }
+}
+
+fn is_identifier_start(ch: char) -> bool {
+    ch == '_' || ch == '$' || ch.is_alphabetic()
+}
+
+fn is_identifier_part(ch: char) -> bool {
+    is_identifier_start(ch) || ch.is_alphanumeric()
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lex()
     }
 }
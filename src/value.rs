@@ -1,55 +1,193 @@
-use std::collections::HashMap;
+use std::mem;
+use std::slice;
 
-#[derive(Debug, PartialEq)]
+use crate::map::{self, Map};
+use crate::number::Number;
+
+#[derive(Debug, Default)]
 pub enum Json {
+    #[default]
     Null,
     String(String),
-    Number(f64),
+    Number(Number),
     Boolean(bool),
     Array(Vec<Json>),
-    Object(HashMap<String, Json>),
+    Object(Map),
 }
 
 impl Json {
     pub fn is_null(&self) -> bool {
-        match *self {
-            Json::Null => true,
-            _ => false,
-        }
+        matches!(*self, Json::Null)
     }
 
     pub fn is_string(&self) -> bool {
-        match *self {
-            Json::String(_) => true,
-            _ => false,
-        }
+        matches!(*self, Json::String(_))
     }
 
     pub fn is_number(&self) -> bool {
-        match *self {
-            Json::Number(_) => true,
-            _ => false,
-        }
+        matches!(*self, Json::Number(_))
     }
 
     pub fn is_boolean(&self) -> bool {
-        match *self {
-            Json::Boolean(_) => true,
-            _ => false,
-        }
+        matches!(*self, Json::Boolean(_))
     }
 
     pub fn is_array(&self) -> bool {
-        match *self {
-            Json::Array(_) => true,
-            _ => false,
-        }
+        matches!(*self, Json::Array(_))
     }
 
     pub fn is_object(&self) -> bool {
-        match *self {
-            Json::Object(_) => true,
-            _ => false,
+        matches!(*self, Json::Object(_))
+    }
+
+    /// 取出这个值, 原来的位置留下`Json::Null`.
+    pub fn take(&mut self) -> Json {
+        mem::replace(self, Json::Null)
+    }
+
+    pub fn into_array(mut self) -> Option<Vec<Json>> {
+        match self {
+            Json::Array(ref mut array) => Some(mem::take(array)),
+            _ => None,
+        }
+    }
+
+    pub fn into_object(mut self) -> Option<Map> {
+        match self {
+            Json::Object(ref mut object) => Some(mem::take(object)),
+            _ => None,
+        }
+    }
+
+    pub fn into_string(mut self) -> Option<String> {
+        match self {
+            Json::String(ref mut s) => Some(mem::take(s)),
+            _ => None,
+        }
+    }
+}
+
+/// 默认的析构是递归的, 嵌套很深的值会导致栈溢出, 所以这里先把子节点移到一个栈上, 再逐个释放.
+///
+/// 实现了`Drop`之后, 不能再用`match json { Json::Array(v) => v, .. }`这样的模式把内容移出来,
+/// 需要改用`into_array`, `into_object`, `into_string`或者`take`.
+impl Drop for Json {
+    fn drop(&mut self) {
+        let mut stack = match *self {
+            Json::Array(ref mut array) if !array.is_empty() => mem::take(array),
+            Json::Object(ref mut object) if !object.is_empty() => {
+                object.drain().map(|(_, value)| value).collect()
+            }
+            _ => return,
+        };
+
+        while let Some(mut json) = stack.pop() {
+            match json {
+                Json::Array(ref mut array) => stack.append(array),
+                Json::Object(ref mut object) => {
+                    stack.extend(object.drain().map(|(_, value)| value))
+                }
+                _ => {}
+            }
         }
     }
 }
+
+// 正在复制的数组或者对象: 还没有复制的子节点, 已经复制好的部分, 以及对象中正在复制的值所对应的键.
+enum Frame<'a> {
+    Array(slice::Iter<'a, Json>, Vec<Json>),
+    Object(map::Iter<'a>, Map, Option<String>),
+}
+
+/// 与析构一样, 默认的复制也是递归的, 所以这里把正在复制的数组和对象保存在一个显式的栈上.
+impl Clone for Json {
+    fn clone(&self) -> Self {
+        let mut stack: Vec<Frame<'_>> = Vec::new();
+        let mut next = self;
+
+        loop {
+            let mut value = match *next {
+                Json::Null => Some(Json::Null),
+                Json::String(ref s) => Some(Json::String(s.clone())),
+                Json::Number(ref n) => Some(Json::Number(n.clone())),
+                Json::Boolean(b) => Some(Json::Boolean(b)),
+                Json::Array(ref array) => {
+                    stack.push(Frame::Array(array.iter(), Vec::with_capacity(array.len())));
+                    None
+                }
+                Json::Object(ref object) => {
+                    stack.push(Frame::Object(
+                        object.iter(),
+                        Map::with_capacity(object.len()),
+                        None,
+                    ));
+                    None
+                }
+            };
+
+            // 把复制好的值放进栈顶的容器, 然后找到下一个需要复制的子节点; 容器复制完了就把它作为一个值交给外层.
+            loop {
+                let frame = match stack.last_mut() {
+                    Some(frame) => frame,
+                    None => return value.expect("the root has been cloned"),
+                };
+
+                let child = match *frame {
+                    Frame::Array(ref mut iter, ref mut array) => {
+                        array.extend(value.take());
+                        iter.next()
+                    }
+                    Frame::Object(ref mut iter, ref mut object, ref mut key) => {
+                        if let (Some(key), Some(value)) = (key.take(), value.take()) {
+                            object.insert(key, value);
+                        }
+                        iter.next().map(|(k, child)| {
+                            *key = Some(k.clone());
+                            child
+                        })
+                    }
+                };
+
+                if let Some(child) = child {
+                    next = child;
+                    break;
+                }
+
+                value = stack.pop().map(|frame| match frame {
+                    Frame::Array(_, array) => Json::Array(array),
+                    Frame::Object(_, object, _) => Json::Object(object),
+                });
+            }
+        }
+    }
+}
+
+/// 逐对比较, 不使用递归. 对象之间的比较与键的顺序无关.
+impl PartialEq for Json {
+    fn eq(&self, other: &Json) -> bool {
+        let mut stack = vec![(self, other)];
+
+        while let Some((a, b)) = stack.pop() {
+            match (a, b) {
+                (Json::Null, Json::Null) => {}
+                (Json::String(a), Json::String(b)) if a == b => {}
+                (Json::Number(a), Json::Number(b)) if a == b => {}
+                (Json::Boolean(a), Json::Boolean(b)) if a == b => {}
+                (Json::Array(a), Json::Array(b)) if a.len() == b.len() => {
+                    stack.extend(a.iter().zip(b.iter()))
+                }
+                (Json::Object(a), Json::Object(b)) if a.len() == b.len() => {
+                    for (key, value) in a {
+                        match b.get(key) {
+                            Some(other) => stack.push((value, other)),
+                            None => return false,
+                        }
+                    }
+                }
+                _ => return false,
+            }
+        }
+
+        true
+    }
+}
@@ -1,7 +1,12 @@
 #[cfg(test)]
 pub mod code_generator_tests {
-    use json::code_generator::CodeGenerator;
+    use json::code_generator::{
+        CodeGenerator, GeneratorOptions, JsonWriter, NonFinite, PrettyConfig,
+    };
+    use json::error::{JsonError, Misuse};
     use json::hash_map;
+    use json::map::Map;
+    use json::parser::ParserOptions;
     use json::value::Json;
 
     #[test]
@@ -12,32 +17,33 @@ pub mod code_generator_tests {
     #[test]
     pub fn should_generate_null() {
         let mut gen = CodeGenerator::new();
-        gen.gather(&Json::Null);
+        gen.gather(&Json::Null).unwrap();
         assert_eq!("null", gen.product())
     }
 
     #[test]
     pub fn should_generate_bool() {
         let mut gen = CodeGenerator::new();
-        gen.gather(&Json::Boolean(true));
+        gen.gather(&Json::Boolean(true)).unwrap();
         assert_eq!("true", gen.product());
 
         let mut gen = CodeGenerator::new();
-        gen.gather(&Json::Boolean(false));
+        gen.gather(&Json::Boolean(false)).unwrap();
         assert_eq!("false", gen.product());
     }
 
     #[test]
     pub fn should_generate_number() {
         let mut gen = CodeGenerator::new();
-        gen.gather(&Json::Number(1.23));
+        gen.gather(&Json::from(1.23)).unwrap();
         assert_eq!("1.23", gen.product());
     }
 
     #[test]
     pub fn should_generate_string() {
         let mut gen = CodeGenerator::new();
-        gen.gather(&Json::String(String::from("Hello world")));
+        gen.gather(&Json::String(String::from("Hello world")))
+            .unwrap();
         assert_eq!("\"Hello world\"", gen.product());
     }
 
@@ -45,20 +51,314 @@ pub mod code_generator_tests {
     pub fn should_generate_array() {
         let mut gen = CodeGenerator::new();
         gen.gather(&Json::Array(vec![
-            Json::Number(1.),
-            Json::Number(2.),
-            Json::Number(3.),
-            Json::Number(4.),
-        ]));
+            Json::from(1),
+            Json::from(2),
+            Json::from(3),
+            Json::from(4),
+        ]))
+        .unwrap();
         assert_eq!("[1,2,3,4]", gen.product());
     }
 
     #[test]
     pub fn should_generate_object() {
         let mut gen = CodeGenerator::new();
-        gen.gather(&Json::Object(hash_map! {
+        gen.gather(&Json::from(hash_map! {
             String::from("k1") => Json::String(String::from("v1"))
-        }));
+        }))
+        .unwrap();
         assert_eq!(r#"{"k1":"v1"}"#, gen.product());
     }
+
+    #[test]
+    pub fn should_escape_strings_as_json() {
+        let mut gen = CodeGenerator::new();
+        gen.gather(&Json::from(
+            "quote\" back\\ it's \u{1b}\u{0}\n\t\u{8}\u{c}\r é",
+        ))
+        .unwrap();
+        assert_eq!(
+            r#""quote\" back\\ it's \u001b\u0000\n\t\b\f\r é""#,
+            gen.product()
+        );
+    }
+
+    #[test]
+    pub fn should_escape_object_keys() {
+        let mut gen = CodeGenerator::new();
+        gen.gather(&Json::from(hash_map! {
+            String::from("a\"b") => Json::Null
+        }))
+        .unwrap();
+        assert_eq!(r#"{"a\"b":null}"#, gen.product());
+    }
+
+    #[test]
+    pub fn should_generate_ascii_only() {
+        let mut gen = CodeGenerator::with_options(GeneratorOptions::new().ascii_only(true));
+        gen.gather(&Json::from("caf\u{e9} \u{1f600}")).unwrap();
+        assert_eq!(r#""caf\u00e9 \ud83d\ude00""#, gen.product());
+    }
+
+    #[test]
+    pub fn should_generate_html_safe() {
+        let mut gen = CodeGenerator::with_options(GeneratorOptions::new().html_safe(true));
+        gen.gather(&Json::from("</script>&\u{2028}\u{2029}"))
+            .unwrap();
+        assert_eq!(r#""\u003c/script\u003e\u0026\u2028\u2029""#, gen.product());
+    }
+
+    fn nested() -> Json {
+        json::parse(r#"{"name":"json","tags":["a","b"],"empty":[],"meta":{},"nested":{"k":null}}"#)
+            .unwrap()
+    }
+
+    #[test]
+    pub fn should_generate_pretty_with_defaults() {
+        let expected = r#"{
+  "name": "json",
+  "tags": [
+    "a",
+    "b"
+  ],
+  "empty": [],
+  "meta": {},
+  "nested": {
+    "k": null
+  }
+}"#;
+        assert_eq!(expected, json::stringify_pretty(nested()));
+    }
+
+    #[test]
+    pub fn should_generate_pretty_with_custom_config() {
+        let config = PrettyConfig::new()
+            .tabs(1)
+            .newline("\r\n")
+            .space_after_colon(false)
+            .trailing_newline(true)
+            .inline_empty(false);
+        let mut gen = CodeGenerator::with_options(GeneratorOptions::new().pretty(config));
+        gen.gather(&nested()).unwrap();
+        let expected = "{\r\n\t\"name\":\"json\",\r\n\t\"tags\":[\r\n\t\t\"a\",\r\n\t\t\"b\"\r\n\t],\r\n\t\"empty\":[\r\n\t],\r\n\t\"meta\":{\r\n\t},\r\n\t\"nested\":{\r\n\t\t\"k\":null\r\n\t}\r\n}\r\n";
+        assert_eq!(expected, gen.product());
+    }
+
+    #[test]
+    pub fn should_generate_pretty_scalars_and_empty_containers() {
+        assert_eq!("1", json::stringify_pretty(1));
+        assert_eq!("[]", json::stringify_pretty(Vec::<Json>::new()));
+        let config = PrettyConfig::new().spaces(4).trailing_newline(true);
+        assert_eq!(
+            "[\n    1\n]\n",
+            json::stringify_with(vec![Json::from(1)], GeneratorOptions::new().pretty(config))
+                .unwrap()
+        );
+    }
+
+    #[test]
+    pub fn should_generate_deeply_nested_values_without_recursion() {
+        let depth = 100_000;
+        let mut json = Json::from(vec![Json::from(hash_map! {})]);
+        for _ in 0..depth {
+            json = Json::from(vec![json]);
+        }
+
+        let expected = format!("{}[{{}}]{}", "[".repeat(depth), "]".repeat(depth));
+        let mut gen = CodeGenerator::new();
+        gen.gather(&json).unwrap();
+        assert_eq!(expected, gen.product());
+    }
+
+    #[test]
+    pub fn should_generate_json5() {
+        let json = Json::from(vec![
+            Json::from(f64::NAN),
+            Json::from(f64::INFINITY),
+            Json::from(f64::NEG_INFINITY),
+            Json::from(1.5),
+            Json::from(r#"say "hi""#),
+            Json::from("it's"),
+            Json::from(r#"'"'"#),
+        ]);
+        assert_eq!(
+            json::stringify_with(json, GeneratorOptions::new().json5(true)).unwrap(),
+            r#"[NaN,Infinity,-Infinity,1.5,'say "hi"',"it's","'\"'"]"#
+        );
+    }
+
+    #[test]
+    pub fn should_quote_non_ascii_keys_in_json5() {
+        let json = Json::from(hash_map! {
+            "a\u{bd}".to_owned() => Json::from(1),
+            "x\u{b2}".to_owned() => Json::from(2),
+            "_a1$".to_owned() => Json::from(3)
+        });
+        let text = json::stringify_with(json.clone(), GeneratorOptions::new().json5(true)).unwrap();
+        assert!(text.contains("\"a\u{bd}\":1"), "{}", text);
+        assert!(text.contains("\"x\u{b2}\":2"), "{}", text);
+        assert!(text.contains("_a1$:3"), "{}", text);
+
+        assert_eq!(json::parse_with(&text, ParserOptions::json5()), Ok(json));
+    }
+
+    #[test]
+    pub fn should_generate_json5_keys_and_trailing_commas() {
+        let mut object = Map::new();
+        object.insert("name".to_owned(), Json::from("json"));
+        object.insert("$_k\u{e9}y2".to_owned(), Json::Null);
+        object.insert("2d".to_owned(), Json::from(2));
+        object.insert("a-b".to_owned(), Json::from(vec![Json::from(true)]));
+        object.insert("".to_owned(), Json::from(hash_map! {}));
+        object.insert("Infinity".to_owned(), Json::from(0));
+        let json = Json::from(object);
+
+        let options = GeneratorOptions::new().json5(true);
+        assert_eq!(
+            json::stringify_with(json.clone(), options.clone()).unwrap(),
+            r#"{name:"json","$_kéy2":null,"2d":2,"a-b":[true],"":{},"Infinity":0}"#
+        );
+
+        let pretty = options.pretty(PrettyConfig::new().trailing_commas(true));
+        let text = json::stringify_with(json.clone(), pretty).unwrap();
+        assert_eq!(
+            text,
+            "{\n  name: \"json\",\n  \"$_k\u{e9}y2\": null,\n  \"2d\": 2,\n  \"a-b\": [\n    true,\n  ],\n  \"\": {},\n  \"Infinity\": 0,\n}"
+        );
+        assert_eq!(
+            json::parse_with(&text, ParserOptions::json5()),
+            Ok(json.clone())
+        );
+
+        // 非JSON5模式下忽略trailing_commas.
+        let pretty = GeneratorOptions::new().pretty(PrettyConfig::new().trailing_commas(true));
+        assert_eq!(
+            json::parse(&json::stringify_with(json.clone(), pretty).unwrap()),
+            Ok(json)
+        );
+    }
+
+    #[test]
+    pub fn should_apply_non_finite_policy() {
+        let json = Json::from(vec![Json::from(f64::NAN), Json::from(f64::NEG_INFINITY)]);
+
+        assert_eq!(json::stringify(json.clone()), "[null,null]");
+        assert_eq!(
+            json::stringify_with(
+                json.clone(),
+                GeneratorOptions::new().non_finite(NonFinite::Literal)
+            )
+            .unwrap(),
+            "[NaN,-Infinity]"
+        );
+        assert_eq!(
+            json::stringify_with(
+                vec![Json::from(1), Json::from(f64::INFINITY)],
+                GeneratorOptions::new().non_finite(NonFinite::Error)
+            ),
+            Err(JsonError::NonFiniteNumber {
+                value: f64::INFINITY
+            })
+        );
+        assert_eq!(
+            json::stringify_with(
+                json,
+                GeneratorOptions::new()
+                    .non_finite(NonFinite::Error)
+                    .json5(true)
+            )
+            .unwrap(),
+            "[NaN,-Infinity]"
+        );
+    }
+
+    #[test]
+    pub fn should_generate_shortest_round_trip_floats() {
+        for &(f, expected) in &[
+            (1e300, "1e300"),
+            (-1.5e-7, "-1.5e-7"),
+            (1e21, "1e21"),
+            (1.2345678901234567e20, "123456789012345670000"),
+            (0.000001, "0.000001"),
+            (0.1 + 0.2, "0.30000000000000004"),
+            (f64::MAX, "1.7976931348623157e308"),
+            (5e-324, "5e-324"),
+            (-0.0, "-0"),
+        ] {
+            let text = json::stringify(f);
+            assert_eq!(text, expected);
+            assert_eq!(
+                json::parse(&text).ok().and_then(|json| match json {
+                    Json::Number(ref n) => n.as_f64(),
+                    _ => None,
+                }),
+                Some(f)
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_stream_with_json_writer() {
+        let expected =
+            json::parse(r#"{"rows": [{"id": 0}, {"id": 1}, {"id": 2}], "tags": [], "ok": true}"#)
+                .unwrap();
+
+        for options in [
+            GeneratorOptions::new(),
+            GeneratorOptions::new().pretty(PrettyConfig::new().trailing_newline(true)),
+        ] {
+            let mut writer = JsonWriter::with_options(options.clone());
+            writer.begin_object().unwrap();
+            writer.key("rows").unwrap();
+            writer.begin_array().unwrap();
+            for id in 0..3 {
+                writer.begin_object().unwrap();
+                writer.key("id").unwrap();
+                writer.value(&Json::from(id)).unwrap();
+                writer.end().unwrap();
+            }
+            writer.end().unwrap();
+            writer.key("tags").unwrap();
+            writer.value(&Json::Array(Vec::new())).unwrap();
+            writer.key("ok").unwrap();
+            writer.value(&Json::from(true)).unwrap();
+            writer.end().unwrap();
+
+            assert_eq!(
+                writer.finish().unwrap(),
+                json::stringify_with(expected.clone(), options).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_reject_mis_nested_writes() {
+        let misused = |misuse| Err(JsonError::InvalidWrite { misuse });
+
+        let mut writer = JsonWriter::new();
+        assert_eq!(writer.key("a"), misused(Misuse::KeyOutsideObject));
+        assert_eq!(writer.end(), misused(Misuse::UnbalancedEnd));
+        writer.begin_array().unwrap();
+        assert_eq!(writer.key("a"), misused(Misuse::KeyOutsideObject));
+        writer.begin_object().unwrap();
+        assert_eq!(writer.value(&Json::Null), misused(Misuse::ValueWithoutKey));
+        writer.key("a").unwrap();
+        assert_eq!(writer.key("b"), misused(Misuse::KeyWithoutValue));
+        assert_eq!(writer.end(), misused(Misuse::KeyWithoutValue));
+        writer.value(&Json::from("x")).unwrap();
+        writer.end().unwrap();
+        writer.end().unwrap();
+        assert_eq!(writer.end(), misused(Misuse::UnbalancedEnd));
+        assert_eq!(writer.value(&Json::Null), misused(Misuse::MultipleValues));
+        assert_eq!(writer.finish().unwrap(), r#"[{"a":"x"}]"#);
+
+        let mut writer = JsonWriter::new();
+        assert_eq!(writer.begin_array(), Ok(()));
+        assert_eq!(
+            writer.finish(),
+            Err(JsonError::InvalidWrite {
+                misuse: Misuse::Unfinished
+            })
+        );
+    }
 }
@@ -0,0 +1,82 @@
+#[cfg(test)]
+pub mod diagnostic_tests {
+    use json::diagnostic::{self, Diagnostic};
+    use json::parser::{DuplicateKeys, ParserOptions};
+
+    fn render(source: &str) -> String {
+        diagnostic::render(source, &json::parse(source).unwrap_err())
+    }
+
+    #[test]
+    pub fn should_render_snippet_with_caret() {
+        let source = "{\n  \"a\": 1,\n  \"b\": 2,\n}";
+        let expected = r#"error: trailing comma not allowed
+ --> line 3, column 9
+  |
+3 |   "b": 2,
+  |         ^ remove this comma
+  |
+  = hint: JSON does not allow a comma after the last element
+"#;
+        assert_eq!(render(source), expected);
+    }
+
+    #[test]
+    pub fn should_suggest_literals() {
+        for (source, expected) in &[
+            ("[True]", "true"),
+            ("[tru]", "true"),
+            ("[Fasle]", "false"),
+            ("{\"a\": NULL}", "null"),
+            ("[nul]", "null"),
+        ] {
+            let error = json::parse(source).unwrap_err();
+            let diagnostic = Diagnostic::new(&error);
+            assert_eq!(
+                diagnostic.hint,
+                Some(format!("did you mean `{}`?", expected)),
+                "{}",
+                source
+            );
+        }
+
+        let rendered = render("[True]");
+        assert!(rendered.contains("1 | [True]\n  |  ^^^^ not a JSON literal\n"));
+    }
+
+    #[test]
+    pub fn should_hint_missing_comma() {
+        let rendered = render("[1 2]");
+        assert!(rendered.starts_with("error: missing comma between elements\n"));
+        assert!(rendered.contains("  |    ^ expected `,` before this\n"));
+    }
+
+    #[test]
+    pub fn should_align_caret_with_tabs_and_wide_gutter() {
+        let source = format!("{}\t[1, @]", "\n".repeat(9));
+        let rendered = render(&source);
+        assert!(rendered.contains("10 | \t[1, @]\n   | \t    ^ not valid in JSON\n"));
+    }
+
+    #[test]
+    pub fn should_point_after_last_line_on_eof() {
+        let rendered = render("[1,");
+        assert!(rendered.contains("1 | [1,\n  |    ^ expected more input here\n"));
+    }
+
+    #[test]
+    pub fn should_point_at_both_duplicate_keys() {
+        let source = "{\n  \"id\": 1,\n  \"id\": 2\n}";
+        let options = ParserOptions::new().duplicate_keys(DuplicateKeys::Error);
+        let error = json::parse_with(source, options).unwrap_err();
+        let expected = r#"error: duplicate key "id"
+ --> line 3, column 3
+  |
+3 |   "id": 2
+  |   ^^^^ duplicate key
+  |
+  = hint: the key was first defined at line 2, column 3
+"#;
+        assert_eq!(diagnostic::render(source, &error), expected);
+    }
+}
@@ -0,0 +1,111 @@
+#[cfg(test)]
+pub mod event_reader_tests {
+    use json::error::JsonError;
+    use json::event_reader::{Event, EventReader};
+    use json::position::{Position, Span};
+    use json::token::Token;
+    use json::value::Json;
+
+    #[test]
+    pub fn should_read_events_in_document_order() {
+        let reader = EventReader::new(r#"{"a": [1, true, {}], "b": null}"#);
+        let events: Vec<Event> = reader.map(Result::unwrap).collect();
+
+        assert_eq!(
+            events,
+            vec![
+                Event::StartObject,
+                Event::Key("a".to_owned()),
+                Event::StartArray,
+                Event::Scalar(Json::from(1)),
+                Event::Scalar(Json::from(true)),
+                Event::StartObject,
+                Event::EndObject,
+                Event::EndArray,
+                Event::Key("b".to_owned()),
+                Event::Scalar(Json::Null),
+                Event::EndObject,
+            ]
+        );
+    }
+
+    #[test]
+    pub fn should_report_json_pointer() {
+        let mut reader = EventReader::new(r#"{"a": [1, {"m~n": 2, "x/y": [3]}]}"#);
+        let mut pointers = Vec::new();
+        while let Some(event) = reader.next_event() {
+            pointers.push((event.unwrap(), reader.pointer()));
+        }
+
+        let expected: Vec<(Event, &str)> = vec![
+            (Event::StartObject, ""),
+            (Event::Key("a".to_owned()), "/a"),
+            (Event::StartArray, "/a"),
+            (Event::Scalar(Json::from(1)), "/a/0"),
+            (Event::StartObject, "/a/1"),
+            (Event::Key("m~n".to_owned()), "/a/1/m~0n"),
+            (Event::Scalar(Json::from(2)), "/a/1/m~0n"),
+            (Event::Key("x/y".to_owned()), "/a/1/x~1y"),
+            (Event::StartArray, "/a/1/x~1y"),
+            (Event::Scalar(Json::from(3)), "/a/1/x~1y/0"),
+            (Event::EndArray, "/a/1/x~1y"),
+            (Event::EndObject, "/a/1"),
+            (Event::EndArray, "/a"),
+            (Event::EndObject, ""),
+        ];
+        assert_eq!(pointers.len(), expected.len());
+        for ((event, pointer), (expected_event, expected_pointer)) in
+            pointers.iter().zip(expected.iter())
+        {
+            assert_eq!(event, expected_event);
+            assert_eq!(pointer, expected_pointer);
+        }
+    }
+
+    #[test]
+    pub fn should_validate_nesting() {
+        let mut reader = EventReader::new("[1}");
+        assert_eq!(reader.next_event(), Some(Ok(Event::StartArray)));
+        assert_eq!(reader.next_event(), Some(Ok(Event::Scalar(Json::from(1)))));
+        assert_eq!(
+            reader.next_event(),
+            Some(Err(JsonError::UnexpectedToken {
+                token: Token::BraceOff,
+                span: Span::new(Position::new(2, 1, 3), Position::new(3, 1, 4)),
+            }))
+        );
+        assert_eq!(reader.next_event(), None);
+
+        let mut reader = EventReader::new("[] []");
+        assert_eq!(reader.next_event(), Some(Ok(Event::StartArray)));
+        assert_eq!(reader.next_event(), Some(Ok(Event::EndArray)));
+        assert_eq!(
+            reader.next_event(),
+            Some(Err(JsonError::TrailingCharacters {
+                position: Position::new(3, 1, 4),
+            }))
+        );
+    }
+
+    #[test]
+    pub fn should_extract_values_without_building_the_tree() {
+        let source = format!(
+            "[{}]",
+            (0..1000)
+                .map(|i| format!(r#"{{"id": {}, "tags": ["a", "b"]}}"#, i))
+                .collect::<Vec<_>>()
+                .join(",")
+        );
+
+        let mut reader = EventReader::new(&source);
+        let mut sum = 0;
+        while let Some(event) = reader.next_event() {
+            if let Event::Scalar(Json::Number(ref n)) = event.unwrap() {
+                if reader.pointer().ends_with("/id") {
+                    sum += n.as_u64().unwrap();
+                }
+            }
+        }
+        assert_eq!(sum, 999 * 1000 / 2);
+    }
+}
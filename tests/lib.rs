@@ -1,2 +1,270 @@
 #[cfg(test)]
-pub mod lib_tests {}
+pub mod lib_tests {
+    use json::code_generator::{GeneratorOptions, NonFinite};
+    use json::error::JsonError;
+    use json::position::Position;
+    use json::value::Json;
+    use std::io::{self, Read, Write};
+
+    #[test]
+    pub fn should_parse_valid_input() {
+        assert_eq!(json::parse("null"), Ok(Json::Null));
+    }
+
+    #[test]
+    pub fn should_not_panic_on_malformed_input() {
+        let err = json::parse("[1, }").unwrap_err();
+        assert_eq!(err.to_string(), "Unexpected token `}` at line 1 column 5");
+    }
+
+    #[test]
+    pub fn should_round_trip_escaped_strings() {
+        let source = "tab\t quote\" nul\u{0} esc\u{1b} emoji \u{1f600}";
+        let text = json::stringify(source);
+        assert_eq!(json::parse(&text), Ok(Json::from(source)));
+    }
+
+    #[test]
+    pub fn should_reject_trailing_content() {
+        assert_eq!(
+            json::parse("1 2 {"),
+            Err(JsonError::TrailingCharacters {
+                position: Position::new(2, 1, 3),
+            })
+        );
+        assert_eq!(
+            json::parse("[]\n @"),
+            Err(JsonError::TrailingCharacters {
+                position: Position::new(4, 2, 2),
+            })
+        );
+        assert_eq!(
+            json::parse(" {} \n\t"),
+            Ok(Json::from(json::map::Map::new()))
+        );
+    }
+
+    #[test]
+    pub fn should_reject_empty_input() {
+        assert_eq!(
+            json::parse(""),
+            Err(JsonError::UnexpectedEof {
+                position: Position::new(0, 1, 1),
+            })
+        );
+        assert_eq!(
+            json::parse("  \n"),
+            Err(JsonError::UnexpectedEof {
+                position: Position::new(3, 2, 1),
+            })
+        );
+    }
+
+    #[test]
+    pub fn should_parse_prefix_and_report_consumed_bytes() {
+        let text = r#"  {"a": [1, 2]} trailing text"#;
+        let (json, consumed) = json::parse_prefix(text).unwrap();
+        assert_eq!(json, json::parse(r#"{"a": [1, 2]}"#).unwrap());
+        assert_eq!(&text[consumed..], " trailing text");
+
+        assert_eq!(json::parse_prefix("12 34"), Ok((Json::from(12), 2)));
+        assert!(json::parse_prefix("[1,").is_err());
+    }
+
+    // 每次最多给出`chunk`个字节, 在`fail_at`处读取失败.
+    struct Chunked<'a> {
+        bytes: &'a [u8],
+        chunk: usize,
+        fail_at: Option<usize>,
+        offset: usize,
+    }
+
+    impl<'a> Read for Chunked<'a> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.fail_at == Some(self.offset) {
+                return Err(io::Error::new(io::ErrorKind::ConnectionReset, "reset"));
+            }
+            let end = (self.offset + self.chunk.min(buf.len()))
+                .min(self.bytes.len())
+                .min(self.fail_at.unwrap_or(usize::MAX));
+            let n = end - self.offset;
+            buf[..n].copy_from_slice(&self.bytes[self.offset..end]);
+            self.offset = end;
+            Ok(n)
+        }
+    }
+
+    #[test]
+    pub fn should_parse_from_reader() {
+        let text = format!(
+            "{{\"items\": [{}\"é😀\\u00e9\"],\n \"x\": true}}",
+            "1, ".repeat(5000)
+        );
+        for &chunk in &[1, 3, 7, 8192] {
+            let reader = Chunked {
+                bytes: text.as_bytes(),
+                chunk,
+                fail_at: None,
+                offset: 0,
+            };
+            assert_eq!(json::from_reader(reader), json::parse(&text));
+        }
+        assert_eq!(
+            json::from_reader(&b" [1] "[..]),
+            Ok(Json::from(vec![Json::from(1)]))
+        );
+    }
+
+    #[test]
+    pub fn should_report_positions_across_buffer_refills() {
+        let text = format!("[\n{}\n tru]", "\"é\", ".repeat(3000));
+        let expected = json::parse(&text).unwrap_err();
+        assert_eq!(expected.position().line, 3);
+        assert_eq!(json::from_reader(text.as_bytes()), Err(expected));
+
+        let mut bytes = text.into_bytes();
+        bytes[2] = 0xff;
+        assert_eq!(
+            json::from_reader(&bytes[..]),
+            Err(JsonError::InvalidUtf8 {
+                position: Position {
+                    offset: 2,
+                    line: 2,
+                    column: 1
+                }
+            })
+        );
+        assert_eq!(
+            json::from_reader(&"\"é\"".as_bytes()[..2]),
+            Err(JsonError::InvalidUtf8 {
+                position: Position {
+                    offset: 1,
+                    line: 1,
+                    column: 2
+                }
+            })
+        );
+    }
+
+    #[test]
+    pub fn should_distinguish_io_errors_from_syntax_errors() {
+        let text = br#"{"a": [1, 2, 3]}"#;
+        let reader = Chunked {
+            bytes: text,
+            chunk: 4,
+            fail_at: Some(8),
+            offset: 0,
+        };
+        let err = json::from_reader(reader).unwrap_err();
+        match err {
+            JsonError::Io {
+                ref error,
+                position,
+            } => {
+                assert_eq!(error.kind(), io::ErrorKind::ConnectionReset);
+                assert_eq!(position.offset, 8);
+            }
+            ref other => panic!("expected an I/O error, got {:?}", other),
+        }
+        assert!(std::error::Error::source(&err).is_some());
+
+        // 值已经读完, 检查后面的内容时读取失败.
+        let reader = Chunked {
+            bytes: b"[1] ",
+            chunk: 4,
+            fail_at: Some(4),
+            offset: 0,
+        };
+        match json::from_reader(reader) {
+            Err(JsonError::Io { ref error, .. }) => {
+                assert_eq!(error.kind(), io::ErrorKind::ConnectionReset)
+            }
+            other => panic!("expected an I/O error, got {:?}", other),
+        }
+        assert_eq!(
+            json::from_reader(&b"[1] \xff"[..]),
+            Err(JsonError::InvalidUtf8 {
+                position: Position {
+                    offset: 4,
+                    line: 1,
+                    column: 5
+                }
+            })
+        );
+    }
+
+    // 写入`limit`个字节之后失败.
+    struct Limited {
+        written: Vec<u8>,
+        limit: usize,
+    }
+
+    impl Write for Limited {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            if self.written.len() >= self.limit {
+                return Err(io::Error::new(io::ErrorKind::WriteZero, "full"));
+            }
+            let n = buf.len().min(self.limit - self.written.len());
+            self.written.extend_from_slice(&buf[..n]);
+            Ok(n)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    pub fn should_write_to_io_and_fmt_writers() {
+        let json = json::parse(r#"{"a": [1, "é\n", null], "b": {}}"#).unwrap();
+
+        let mut out = Vec::new();
+        json::to_writer(&mut out, &json).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            json::stringify(json.clone())
+        );
+
+        let mut out = Vec::new();
+        json::to_writer_pretty(&mut out, &json).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            json::stringify_pretty(json.clone())
+        );
+
+        let mut out = String::new();
+        json::to_fmt_writer(&mut out, &json, GeneratorOptions::new().json5(true)).unwrap();
+        assert_eq!(out, r#"{a:[1,"é\n",null],b:{}}"#);
+    }
+
+    #[test]
+    pub fn should_propagate_write_errors() {
+        let json = Json::from(vec![Json::from("x".repeat(100))]);
+        let mut out = Limited {
+            written: Vec::new(),
+            limit: 10,
+        };
+        match json::to_writer(&mut out, &json) {
+            Err(JsonError::Io { ref error, .. }) => {
+                assert_eq!(error.kind(), io::ErrorKind::WriteZero)
+            }
+            other => panic!("expected an I/O error, got {:?}", other),
+        }
+        assert_eq!(out.written, b"[\"xxxxxxxx");
+
+        let mut out = Limited {
+            written: Vec::new(),
+            limit: 100,
+        };
+        assert_eq!(
+            json::to_writer_with(
+                &mut out,
+                &Json::from(f64::INFINITY),
+                GeneratorOptions::new().non_finite(NonFinite::Error)
+            ),
+            Err(JsonError::NonFiniteNumber {
+                value: f64::INFINITY
+            })
+        );
+    }
+}
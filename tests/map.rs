@@ -0,0 +1,64 @@
+#[cfg(test)]
+pub mod map_tests {
+    use json::hash_map;
+    use json::map::Map;
+    use json::value::Json;
+
+    #[test]
+    pub fn should_iterate_in_insertion_order() {
+        let mut map = Map::new();
+        map.insert(String::from("b"), Json::from(1));
+        map.insert(String::from("a"), Json::from(2));
+        map.insert(String::from("c"), Json::from(3));
+        assert_eq!(map.keys().collect::<Vec<_>>(), vec!["b", "a", "c"]);
+
+        assert_eq!(
+            map.insert(String::from("a"), Json::from(4)),
+            Some(Json::from(2))
+        );
+        assert_eq!(map.keys().collect::<Vec<_>>(), vec!["b", "a", "c"]);
+        assert_eq!(map["a"], Json::from(4));
+    }
+
+    #[test]
+    pub fn should_keep_lookup_consistent_after_remove() {
+        let mut map: Map = vec![
+            (String::from("x"), Json::from(1)),
+            (String::from("y"), Json::from(2)),
+            (String::from("z"), Json::from(3)),
+        ]
+        .into_iter()
+        .collect();
+
+        assert_eq!(map.remove("x"), Some(Json::from(1)));
+        assert_eq!(map.remove("x"), None);
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get("y"), Some(&Json::from(2)));
+        assert_eq!(map.get("z"), Some(&Json::from(3)));
+        assert_eq!(map.keys().collect::<Vec<_>>(), vec!["y", "z"]);
+    }
+
+    #[test]
+    pub fn should_compare_regardless_of_order() {
+        let left: Map = hash_map! {
+            String::from("k1") => Json::from(1),
+            String::from("k2") => Json::from(2),
+        }
+        .into();
+        let mut right = Map::new();
+        right.insert(String::from("k2"), Json::from(2));
+        right.insert(String::from("k1"), Json::from(1));
+        assert_eq!(left, right);
+
+        right.insert(String::from("k1"), Json::Null);
+        assert_ne!(left, right);
+    }
+
+    #[test]
+    pub fn should_preserve_source_order_through_round_trip() {
+        let source = r#"{"zebra":1,"apple":{"y":true,"x":null},"mango":[],"banana":"b"}"#;
+        for _ in 0..8 {
+            assert_eq!(json::stringify(json::parse(source).unwrap()), source);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(test)]
+pub mod number_tests {
+    use json::diagnostic::Diagnostic;
+    use json::error::JsonError;
+    use json::number::Number;
+    use json::parser::ParserOptions;
+    use json::value::Json;
+
+    #[test]
+    pub fn should_keep_integers_apart_from_floats() {
+        let n = Number::from(42);
+        assert!(n.is_u64() && n.is_i64() && !n.is_f64());
+        assert_eq!(n.as_u64(), Some(42));
+        assert_eq!(n.as_i64(), Some(42));
+        assert_eq!(n.as_f64(), Some(42.));
+
+        let n = Number::from(-42i64);
+        assert!(!n.is_u64() && n.is_i64());
+        assert_eq!(n.as_u64(), None);
+        assert_eq!(n.as_i64(), Some(-42));
+
+        let n = Number::from(u64::MAX);
+        assert!(n.is_u64() && !n.is_i64());
+        assert_eq!(n.as_i64(), None);
+
+        let n = Number::from(1.5);
+        assert!(n.is_f64());
+        assert_eq!(n.as_i64(), None);
+        assert_eq!(n.as_f64(), Some(1.5));
+    }
+
+    #[test]
+    pub fn should_parse_narrowest_exact_representation() {
+        assert_eq!(
+            json::parse("9007199254740993"),
+            Ok(Json::from(9007199254740993u64))
+        );
+        assert_eq!(
+            json::parse("18446744073709551615"),
+            Ok(Json::from(u64::MAX))
+        );
+        assert_eq!(
+            json::parse("-9223372036854775808"),
+            Ok(Json::from(i64::MIN))
+        );
+        assert_eq!(
+            json::parse("18446744073709551616"),
+            Ok(Json::from(18446744073709551616.))
+        );
+        assert_eq!(json::parse("1.0"), Ok(Json::from(1.)));
+        assert_eq!(json::parse("1e2"), Ok(Json::from(100.)));
+        assert_eq!(json::parse("-0"), Ok(Json::from(-0.)));
+    }
+
+    #[test]
+    pub fn should_round_trip_64_bit_integers() {
+        for source in &[
+            "9007199254740993",
+            "18446744073709551615",
+            "-9223372036854775808",
+        ] {
+            assert_eq!(&json::stringify(json::parse(source).unwrap()), source);
+        }
+        assert_eq!(json::stringify(u64::MAX), "18446744073709551615");
+        assert_eq!(json::stringify(i64::MIN), "-9223372036854775808");
+    }
+
+    #[test]
+    pub fn should_preserve_number_text_in_arbitrary_precision_mode() {
+        let options = ParserOptions::new().arbitrary_precision(true);
+        let source =
+            "[0.1000000000000000055511151231257827,123456789012345678901234567890,-1.50E+3]";
+        let json = json::parse_with(source, options).unwrap();
+        assert_eq!(json::stringify(json), source);
+    }
+
+    #[test]
+    pub fn should_convert_preserved_numbers_on_demand() {
+        let n: Number = "123456789012345678901234567890".parse().unwrap();
+        assert_eq!(n.as_str(), Some("123456789012345678901234567890"));
+        assert_eq!(n.as_u128(), Some(123456789012345678901234567890));
+        assert_eq!(n.as_u64(), None);
+        assert!(!n.is_f64());
+
+        let n: Number = "-170141183460469231731687303715884105728".parse().unwrap();
+        assert_eq!(n.as_i128(), Some(i128::MIN));
+        assert_eq!(n.as_u128(), None);
+
+        let n: Number = "0.1000000000000000055511151231257827".parse().unwrap();
+        assert_eq!(n.as_f64(), Some(0.1));
+        assert_eq!(n.as_i128(), None);
+        assert!(n.is_f64());
+    }
+
+    #[test]
+    pub fn should_reject_invalid_number_text() {
+        for source in &["", "01", " 1", "1 ", "1.", "abc", "1,"] {
+            assert!(source.parse::<Number>().is_err(), "{:?}", source);
+        }
+    }
+
+    #[test]
+    pub fn should_reject_numbers_out_of_f64_range() {
+        for source in &["1e400", "-1e400", "[1, 2e308]", "179769313486231580793728971405303415079934132710037826936173778980444968292764750946649017977587207096330286416692887910946555547851940402630657488671505820681908902000708383676273854845817711531764475730270069855571366959622842914819860834936475292719074168444365510704342711559699508093042880177904174497792"] {
+            match json::parse(source) {
+                Err(JsonError::InvalidNumber { .. }) => {}
+                other => panic!("{}: expected an invalid number, got {:?}", source, other),
+            }
+        }
+        assert_eq!(json::parse("1e-400"), Ok(Json::from(0.0)));
+        assert_eq!(
+            json::parse("1.7976931348623157e308"),
+            Ok(Json::from(f64::MAX))
+        );
+
+        let options = ParserOptions::new().arbitrary_precision(true);
+        let json = json::parse_with("[1e400]", options).unwrap();
+        assert_eq!(json::stringify(json), "[1e400]");
+
+        let error = json::parse("1e400").unwrap_err();
+        assert_eq!(
+            Diagnostic::new(&error).hint.as_deref(),
+            Some("the number is out of range for f64, see `ParserOptions::arbitrary_precision`")
+        );
+    }
+}
@@ -1,7 +1,11 @@
 #[cfg(test)]
 pub mod parser_tests {
+    use json::error::{JsonError, Limit};
     use json::hash_map;
-    use json::parser::Parser;
+    use json::number::Number;
+    use json::parser::{DuplicateKeys, Parser, ParserOptions, DEFAULT_MAX_DEPTH};
+    use json::position::{Position, Span};
+    use json::token::Token;
     use json::value::Json;
 
     #[test]
@@ -12,7 +16,7 @@ pub mod parser_tests {
     #[test]
     pub fn should_parse_null_type() {
         let mut parser = Parser::new("null");
-        let json = parser.parse();
+        let json = parser.parse().unwrap();
 
         assert_eq!(json, Json::Null);
     }
@@ -20,39 +24,39 @@ pub mod parser_tests {
     #[test]
     pub fn should_parse_bool_type() {
         let mut parser = Parser::new("false");
-        let json = parser.parse();
+        let json = parser.parse().unwrap();
         assert_eq!(json, Json::Boolean(false));
 
         let mut parser = Parser::new("true");
-        let json = parser.parse();
+        let json = parser.parse().unwrap();
         assert_eq!(json, Json::Boolean(true));
     }
 
     #[test]
     pub fn should_parse_number_type() {
         let mut parser = Parser::new("1.23");
-        let json = parser.parse();
-        assert_eq!(json, Json::Number(1.23));
+        let json = parser.parse().unwrap();
+        assert_eq!(json, Json::from(1.23));
     }
 
     #[test]
     pub fn should_parse_string_type() {
         let mut parser = Parser::new(r#""Hello world""#);
-        let json = parser.parse();
+        let json = parser.parse().unwrap();
         assert_eq!(json, Json::String(String::from("Hello world")));
     }
 
     #[test]
     pub fn should_parse_array_type() {
         let mut parser = Parser::new(r#"[1, 2, 3, 4]"#);
-        let json = parser.parse();
+        let json = parser.parse().unwrap();
         assert_eq!(
             json,
             Json::Array(vec![
-                Json::Number(1.),
-                Json::Number(2.),
-                Json::Number(3.),
-                Json::Number(4.)
+                Json::from(1),
+                Json::from(2),
+                Json::from(3),
+                Json::from(4)
             ])
         )
     }
@@ -60,10 +64,10 @@ pub mod parser_tests {
     #[test]
     pub fn should_parse_object_type() {
         let mut parser = Parser::new(r#"{ "k1": "v1" }"#);
-        let json = parser.parse();
+        let json = parser.parse().unwrap();
         assert_eq!(
             json,
-            Json::Object(hash_map! {
+            Json::from(hash_map! {
                 String::from("k1") => Json::String(String::from("v1"))
             })
         );
@@ -84,19 +88,577 @@ pub mod parser_tests {
             }
         "#,
         );
-        let json = parser.parse();
+        let json = parser.parse().unwrap();
 
         assert_eq!(
             json,
-            Json::Object(hash_map! {
-                String::from("id") => Json::Number(262804062.),
+            Json::from(hash_map! {
+                String::from("id") => Json::from(262804062),
                 String::from("full_name") => Json::String(String::from("ltoddy/parser-tutorial")),
                 String::from("private") => Json::Boolean(false),
-                String::from("owner") => Json::Object(hash_map!{
+                String::from("owner") => Json::from(hash_map!{
                     String::from("login") => Json::String(String::from("ltoddy")),
-                    String::from("id") => Json::Number(20920763.),
+                    String::from("id") => Json::from(20920763),
                 }),
             })
         );
     }
+
+    #[test]
+    pub fn should_report_unexpected_token() {
+        let mut parser = Parser::new("[1,\n 2 :]");
+        assert_eq!(
+            parser.parse(),
+            Err(JsonError::UnexpectedToken {
+                token: Token::Colon,
+                span: Span::new(Position::new(7, 2, 4), Position::new(8, 2, 5)),
+            })
+        );
+    }
+
+    #[test]
+    pub fn should_report_missing_comma() {
+        let mut parser = Parser::new("[1,\n 2 3]");
+        assert_eq!(
+            parser.parse(),
+            Err(JsonError::MissingComma {
+                position: Position::new(7, 2, 4),
+            })
+        );
+
+        let mut parser = Parser::new(r#"{"a": 1 "b": 2}"#);
+        assert_eq!(
+            parser.parse(),
+            Err(JsonError::MissingComma {
+                position: Position::new(8, 1, 9),
+            })
+        );
+    }
+
+    #[test]
+    pub fn should_report_trailing_comma() {
+        let mut parser = Parser::new("[1, 2,]");
+        assert_eq!(
+            parser.parse(),
+            Err(JsonError::TrailingComma {
+                position: Position::new(5, 1, 6),
+            })
+        );
+
+        let mut parser = Parser::new(r#"{"a": 1, }"#);
+        assert_eq!(
+            parser.parse(),
+            Err(JsonError::TrailingComma {
+                position: Position::new(7, 1, 8),
+            })
+        );
+    }
+
+    #[test]
+    pub fn should_report_unexpected_eof() {
+        let mut parser = Parser::new(r#"{"k1": "#);
+        assert_eq!(
+            parser.parse(),
+            Err(JsonError::UnexpectedEof {
+                position: Position::new(7, 1, 8),
+            })
+        );
+    }
+
+    #[test]
+    pub fn should_report_invalid_literal() {
+        let mut parser = Parser::new("[True]");
+        assert_eq!(
+            parser.parse(),
+            Err(JsonError::InvalidLiteral {
+                literal: String::from("True"),
+                position: Position::new(1, 1, 2),
+            })
+        );
+    }
+
+    #[test]
+    pub fn should_report_unterminated_string() {
+        let mut parser = Parser::new(r#"["abc"#);
+        assert_eq!(
+            parser.parse(),
+            Err(JsonError::UnterminatedString {
+                position: Position::new(1, 1, 2),
+            })
+        );
+    }
+
+    #[test]
+    pub fn should_report_all_errors_when_recovering() {
+        let source = r#"{
+  "a": [1, 2,],
+  "b": tru,
+  "c" 3,
+  "d": [4 5],
+  "e": true,
+}"#;
+        let mut parser = Parser::new(source);
+        let (json, errors) = parser.parse_recovering();
+
+        assert_eq!(
+            json,
+            Some(Json::from(hash_map! {
+                String::from("a") => Json::from(vec![Json::from(1), Json::from(2)]),
+                String::from("d") => Json::from(vec![Json::from(4), Json::from(5)]),
+                String::from("e") => Json::from(true),
+            }))
+        );
+        assert_eq!(
+            errors,
+            vec![
+                JsonError::TrailingComma {
+                    position: Position::new(14, 2, 13),
+                },
+                JsonError::InvalidLiteral {
+                    literal: String::from("tru"),
+                    position: Position::new(25, 3, 8),
+                },
+                JsonError::UnexpectedToken {
+                    token: Token::Number(Number::from(3)),
+                    span: Span::new(Position::new(36, 4, 7), Position::new(37, 4, 8)),
+                },
+                JsonError::MissingComma {
+                    position: Position::new(49, 5, 11),
+                },
+                JsonError::TrailingComma {
+                    position: Position::new(64, 6, 12),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    pub fn should_resynchronise_on_mismatched_brackets() {
+        let mut parser = Parser::new(r#"[{"a": [1, 2}, {"b": }, 3]"#);
+        let (json, errors) = parser.parse_recovering();
+
+        assert_eq!(
+            json,
+            Some(Json::from(vec![
+                Json::from(hash_map! {
+                    String::from("a") => Json::from(vec![Json::from(1), Json::from(2)])
+                }),
+                Json::from(json::map::Map::new()),
+                Json::from(3),
+            ]))
+        );
+        assert_eq!(errors.len(), 2);
+    }
+
+    #[test]
+    pub fn should_close_open_containers_at_eof_when_recovering() {
+        let mut parser = Parser::new(r#"{"a": [1, {"b": "#);
+        let (json, errors) = parser.parse_recovering();
+
+        assert_eq!(
+            json,
+            Some(Json::from(hash_map! {
+                String::from("a") => Json::from(vec![
+                    Json::from(1),
+                    Json::from(json::map::Map::new()),
+                ])
+            }))
+        );
+        assert_eq!(
+            errors,
+            vec![JsonError::UnexpectedEof {
+                position: Position::new(16, 1, 17),
+            }]
+        );
+    }
+
+    #[test]
+    pub fn should_point_trailing_comma_errors_at_the_resynchronised_comma() {
+        let (json, errors) = json::parse_recovering("{,}");
+        assert_eq!(json, Some(Json::from(json::map::Map::new())));
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].position(), Position::new(1, 1, 2));
+
+        let (_, errors) = json::parse_recovering("[1, : ,]");
+        assert_eq!(
+            errors.iter().map(JsonError::position).collect::<Vec<_>>(),
+            vec![Position::new(4, 1, 5), Position::new(6, 1, 7)]
+        );
+    }
+
+    #[test]
+    pub fn should_not_report_errors_for_valid_input_when_recovering() {
+        let mut parser = Parser::new(r#"{"k1": ["v1", 2, null]}"#);
+        let (json, errors) = parser.parse_recovering();
+        assert_eq!(
+            json,
+            Some(json::parse(r#"{"k1": ["v1", 2, null]}"#).unwrap())
+        );
+        assert!(errors.is_empty());
+
+        assert_eq!(json::parse_recovering("").0, None);
+    }
+
+    #[test]
+    pub fn should_limit_nesting_depth() {
+        let source = "[".repeat(200_000);
+        assert_eq!(
+            json::parse(&source),
+            Err(JsonError::LimitExceeded {
+                limit: Limit::Depth,
+                position: Position::new(DEFAULT_MAX_DEPTH, 1, DEFAULT_MAX_DEPTH + 1),
+            })
+        );
+
+        let options = ParserOptions::new().max_depth(2);
+        assert!(json::parse_with(r#"[{"a": 1}]"#, options).is_ok());
+        assert_eq!(
+            json::parse_with(r#"{"a": [[1]]}"#, options),
+            Err(JsonError::LimitExceeded {
+                limit: Limit::Depth,
+                position: Position::new(7, 1, 8),
+            })
+        );
+
+        let (json, errors) = Parser::new(&source).parse_recovering();
+        assert_eq!(json, None);
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    pub fn should_limit_input_size() {
+        let options = ParserOptions::new().max_input_size(8);
+        assert!(json::parse_with("[1, 2]  ", options).is_ok());
+        assert_eq!(
+            json::parse_with("[1, 2, 3]", options),
+            Err(JsonError::LimitExceeded {
+                limit: Limit::InputSize,
+                position: Position::new(8, 1, 9),
+            })
+        );
+        assert!(json::parse_with("[1, 2]   ", options).is_err());
+    }
+
+    #[test]
+    pub fn should_limit_string_length() {
+        let options = ParserOptions::new().max_string_length(3);
+        assert!(json::parse_with(r#"{"abc": "é"}"#, options).is_ok());
+        assert_eq!(
+            json::parse_with(r#"{"abcd": 1}"#, options),
+            Err(JsonError::LimitExceeded {
+                limit: Limit::StringLength,
+                position: Position::new(1, 1, 2),
+            })
+        );
+    }
+
+    #[test]
+    pub fn should_limit_element_count() {
+        let options = ParserOptions::new().max_elements(2);
+        assert!(json::parse_with(r#"[[1, 2], {"a": 1, "b": 2}]"#, options).is_ok());
+        assert!(json::parse_with("[1, 2, 3]", options).is_err());
+        assert_eq!(
+            json::parse_with(r#"{"a": 1, "b": 2, "c": 3}"#, options),
+            Err(JsonError::LimitExceeded {
+                limit: Limit::ElementCount,
+                position: Position::new(22, 1, 23),
+            })
+        );
+    }
+
+    #[test]
+    pub fn should_parse_deeply_nested_values_without_recursion() {
+        let depth = 100_000;
+        let source = format!(
+            r#"{}{{"k": [1, {{}}]}}{}"#,
+            "[".repeat(depth),
+            "]".repeat(depth)
+        );
+        let options = ParserOptions::new().max_depth(usize::MAX);
+
+        let mut json = json::parse_with(&source, options).unwrap();
+        for _ in 0..depth {
+            json = match json {
+                Json::Array(ref mut array) => array.pop().unwrap(),
+                _ => panic!("expected an array"),
+            };
+        }
+        assert_eq!(
+            json,
+            Json::from(hash_map! {
+                "k".to_owned() => Json::from(vec![Json::from(1), Json::from(hash_map!{})])
+            })
+        );
+
+        let (json, errors) =
+            Parser::with_options(&source[..source.len() - 1], options).parse_recovering();
+        assert!(json.is_some());
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    pub fn should_apply_duplicate_key_policy() {
+        let source = r#"{"a": 1, "b": [2], "a": 3, "b": 4, "a": 5}"#;
+        let parse = |policy| json::parse_with(source, ParserOptions::new().duplicate_keys(policy));
+
+        assert_eq!(
+            parse(DuplicateKeys::LastWins),
+            Ok(Json::from(hash_map! {
+                "a".to_owned() => Json::from(5),
+                "b".to_owned() => Json::from(4)
+            }))
+        );
+        assert_eq!(
+            parse(DuplicateKeys::FirstWins),
+            Ok(Json::from(hash_map! {
+                "a".to_owned() => Json::from(1),
+                "b".to_owned() => Json::from(vec![Json::from(2)])
+            }))
+        );
+        assert_eq!(
+            parse(DuplicateKeys::CollectAll),
+            Ok(Json::from(hash_map! {
+                "a".to_owned() => Json::from(vec![Json::from(1), Json::from(3), Json::from(5)]),
+                "b".to_owned() => Json::from(vec![Json::from(vec![Json::from(2)]), Json::from(4)])
+            }))
+        );
+        assert_eq!(
+            parse(DuplicateKeys::Error),
+            Err(JsonError::DuplicateKey {
+                key: "a".to_owned(),
+                first: Span::new(Position::new(1, 1, 2), Position::new(4, 1, 5)),
+                second: Span::new(Position::new(19, 1, 20), Position::new(22, 1, 23)),
+            })
+        );
+    }
+
+    #[test]
+    pub fn should_keep_keys_of_nested_objects_apart() {
+        let options = ParserOptions::new().duplicate_keys(DuplicateKeys::Error);
+        assert!(json::parse_with(r#"{"a": {"a": 1}, "b": {"a": 2}}"#, options).is_ok());
+
+        let (json, errors) =
+            Parser::with_options(r#"{"a": 1, "a": 2, "b": 3}"#, options).parse_recovering();
+        assert_eq!(
+            json,
+            Some(Json::from(hash_map! {
+                "a".to_owned() => Json::from(1),
+                "b".to_owned() => Json::from(3)
+            }))
+        );
+        assert_eq!(errors.len(), 1);
+    }
+
+    #[test]
+    pub fn should_reject_extensions_in_strict_mode() {
+        for source in &[
+            "[1] // comment",
+            "[1, 2,]",
+            r#"{"a": 1,}"#,
+            "['a']",
+            "{a: 1}",
+            "[NaN]",
+            "[-Infinity]",
+            "[+1]",
+            "[0x1F]",
+            "\u{a0}1",
+            "\u{b}1",
+            "[1,\u{2028}2]",
+            "\u{feff}1",
+        ] {
+            assert!(
+                json::parse_with(source, ParserOptions::strict()).is_err(),
+                "{}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_accept_extensions_in_lenient_mode() {
+        let source = r#"
+            // 手写的配置文件
+            {
+                name: 'it\'s "quoted"',
+                $id_1: 0x1F, /* 十六进制 */
+                null: [+1, -0xff, Infinity, -Infinity,],
+            }
+        "#;
+
+        assert_eq!(
+            json::parse_with(source, ParserOptions::lenient()),
+            Ok(Json::from(hash_map! {
+                "name".to_owned() => Json::from("it's \"quoted\""),
+                "$id_1".to_owned() => Json::from(31),
+                "null".to_owned() => Json::from(vec![
+                    Json::from(1),
+                    Json::from(-255),
+                    Json::from(f64::INFINITY),
+                    Json::from(f64::NEG_INFINITY),
+                ])
+            }))
+        );
+
+        match json::parse_with("NaN", ParserOptions::lenient()) {
+            Ok(Json::Number(ref n)) => assert!(n.as_f64().unwrap().is_nan()),
+            other => panic!("expected NaN, got {:?}", other),
+        }
+    }
+
+    #[test]
+    pub fn should_enable_extensions_one_by_one() {
+        let options = ParserOptions::new().allow_trailing_commas(true);
+        assert_eq!(
+            json::parse_with("[1,]", options),
+            Ok(Json::from(vec![Json::from(1)]))
+        );
+        assert!(json::parse_with("[,]", options).is_err());
+        assert!(json::parse_with("['a']", options).is_err());
+
+        let options = ParserOptions::new().allow_unquoted_keys(true);
+        assert_eq!(
+            json::parse_with("{a: 1}", options),
+            Ok(Json::from(hash_map! { "a".to_owned() => Json::from(1) }))
+        );
+        assert_eq!(
+            json::parse_with("[a]", options),
+            Err(JsonError::UnexpectedToken {
+                token: Token::Identifier("a".to_owned()),
+                span: Span::new(Position::new(1, 1, 2), Position::new(2, 1, 3)),
+            })
+        );
+        assert!(json::parse_with("[1,]", options).is_err());
+    }
+
+    #[test]
+    pub fn should_parse_jsonc() {
+        let source = r#"{
+            // 编辑器的配置
+            "editor.tabSize": 4, /* 缩进 */
+            "files.exclude": ["target",],
+        } // 结束
+        "#;
+        let expected = Json::from(hash_map! {
+            "editor.tabSize".to_owned() => Json::from(4),
+            "files.exclude".to_owned() => Json::from(vec![Json::from("target")])
+        });
+
+        assert_eq!(
+            json::parse_with(source, ParserOptions::jsonc()),
+            Ok(expected.clone())
+        );
+        assert_eq!(
+            json::parse_with(source, ParserOptions::jsonc().emit_comments(true)),
+            Ok(expected)
+        );
+        assert_eq!(
+            json::parse_with("[1, /* 2", ParserOptions::jsonc()),
+            Err(JsonError::UnterminatedComment {
+                position: Position::new(4, 1, 5),
+            })
+        );
+    }
+
+    #[test]
+    pub fn should_parse_json5() {
+        let source = r#"// JSON5 示例
+{
+  unquoted: 'and you can quote me on that',
+  singleQuotes: 'I can use "double quotes" here',
+  lineBreaks: "Look, Mom! \
+No \\n's!",
+  hexadecimal: 0xdecaf,
+  leadingDecimalPoint: .8675309, andTrailing: 8675309.,
+  positiveSign: +1,
+  trailingComma: 'in objects', andIn: ['arrays',],
+  "backwardsCompatible": "with JSON",
+  escapes: '\x41\v\0\q',
+}
+"#;
+
+        assert_eq!(
+            json::parse_with(source, ParserOptions::json5()),
+            Ok(Json::from(hash_map! {
+                "unquoted".to_owned() => Json::from("and you can quote me on that"),
+                "singleQuotes".to_owned() => Json::from("I can use \"double quotes\" here"),
+                "lineBreaks".to_owned() => Json::from("Look, Mom! No \\n's!"),
+                "hexadecimal".to_owned() => Json::from(0xdecaf),
+                "leadingDecimalPoint".to_owned() => Json::from(0.8675309),
+                "andTrailing".to_owned() => Json::from(8675309.0),
+                "positiveSign".to_owned() => Json::from(1),
+                "trailingComma".to_owned() => Json::from("in objects"),
+                "andIn".to_owned() => Json::from(vec![Json::from("arrays")]),
+                "backwardsCompatible".to_owned() => Json::from("with JSON"),
+                "escapes".to_owned() => Json::from("A\u{b}\u{0}q")
+            }))
+        );
+        assert!(json::parse(source).is_err());
+    }
+
+    #[test]
+    pub fn should_accept_infinity_and_nan_as_unquoted_keys() {
+        let options = ParserOptions::json5();
+        assert_eq!(
+            json::parse_with(
+                "{Infinity: Infinity, NaN: -Infinity, null: +Infinity}",
+                options
+            ),
+            Ok(Json::from(hash_map! {
+                "Infinity".to_owned() => Json::from(f64::INFINITY),
+                "NaN".to_owned() => Json::from(f64::NEG_INFINITY),
+                "null".to_owned() => Json::from(f64::INFINITY)
+            }))
+        );
+        match json::parse_with("[NaN, 1]", options) {
+            Ok(Json::Array(ref values)) => match values[0] {
+                Json::Number(ref n) => assert!(n.as_f64().is_some_and(f64::is_nan)),
+                ref other => panic!("expected NaN, got {:?}", other),
+            },
+            other => panic!("expected an array, got {:?}", other),
+        }
+        assert_eq!(
+            json::parse_with("[Infinity]", options.allow_nan_infinity(false)),
+            Err(JsonError::UnexpectedToken {
+                token: Token::Identifier("Infinity".to_owned()),
+                span: Span::new(Position::new(1, 1, 2), Position::new(9, 1, 10)),
+            })
+        );
+    }
+
+    #[test]
+    pub fn should_end_line_comments_at_any_line_terminator() {
+        let expected = Json::from(vec![Json::from(1)]);
+        for source in &[
+            "// c\r[1]",
+            "// c\r\n[1]",
+            "// c\u{2028}[1]",
+            "[// c\u{2029}1]",
+        ] {
+            assert_eq!(
+                json::parse_with(source, ParserOptions::json5()),
+                Ok(expected.clone()),
+                "{:?}",
+                source
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_only_accept_extended_whitespace_when_enabled() {
+        let source = "\u{feff}[1,\u{b}\u{c}\u{a0}2,\u{2028}\u{2029}\u{3000}3]";
+        let expected = Json::from(vec![Json::from(1), Json::from(2), Json::from(3)]);
+
+        assert_eq!(
+            json::parse_with(source, ParserOptions::json5()),
+            Ok(expected)
+        );
+        assert_eq!(json::parse(" \t\r\n1 \t\r\n"), Ok(Json::from(1)));
+        assert_eq!(
+            json::parse("\u{a0}1"),
+            Err(JsonError::UnexpectedCharacter {
+                ch: '\u{a0}',
+                position: Position::start()
+            })
+        );
+    }
 }
@@ -0,0 +1,262 @@
+#[cfg(test)]
+pub mod push_parser_tests {
+    use json::error::JsonError;
+    use json::event_reader::Event;
+    use json::parser::ParserOptions;
+    use json::position::Position;
+    use json::push_parser::{Incremental, PushParser};
+    use json::value::Json;
+
+    fn drain(parser: &mut PushParser) -> Vec<Incremental<Event>> {
+        let mut events = Vec::new();
+        loop {
+            let event = parser.next_event().unwrap();
+            match event {
+                Incremental::Ready(_) => events.push(event),
+                _ => {
+                    events.push(event);
+                    return events;
+                }
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_emit_events_as_soon_as_available() {
+        let mut parser = PushParser::new();
+        assert_eq!(drain(&mut parser), vec![Incremental::NeedMoreInput]);
+
+        parser.feed(b"[1, tr").unwrap();
+        assert_eq!(
+            drain(&mut parser),
+            vec![
+                Incremental::Ready(Event::StartArray),
+                Incremental::Ready(Event::Scalar(Json::from(1))),
+                Incremental::NeedMoreInput,
+            ]
+        );
+
+        parser.feed(b"ue]").unwrap();
+        assert_eq!(
+            drain(&mut parser),
+            vec![
+                Incremental::Ready(Event::Scalar(Json::from(true))),
+                Incremental::Ready(Event::EndArray),
+                Incremental::NeedMoreInput,
+            ]
+        );
+
+        parser.finish().unwrap();
+        assert_eq!(drain(&mut parser), vec![Incremental::End]);
+    }
+
+    #[test]
+    pub fn should_wait_for_top_level_scalars_until_finish() {
+        let mut parser = PushParser::new();
+        parser.feed(b" 12").unwrap();
+        assert_eq!(parser.next_value(), Ok(Incremental::NeedMoreInput));
+        parser.feed(b"3").unwrap();
+        assert_eq!(parser.next_value(), Ok(Incremental::NeedMoreInput));
+        parser.finish().unwrap();
+        assert_eq!(parser.next_value(), Ok(Incremental::Ready(Json::from(123))));
+        assert_eq!(parser.next_value(), Ok(Incremental::End));
+    }
+
+    #[test]
+    pub fn should_parse_input_split_at_every_byte() {
+        let text = r#"{"café": ["é😀", "😀", -1.5e3, null, false], /* c */ "k": {}}"#;
+        let options = ParserOptions::jsonc();
+        let expected = json::parse_with(text, options).unwrap();
+        let bytes = text.as_bytes();
+
+        for split in 0..=bytes.len() {
+            let mut parser = PushParser::with_options(options);
+            parser.feed(&bytes[..split]).unwrap();
+            let mut value = parser.next_value().unwrap();
+            if value == Incremental::NeedMoreInput {
+                parser.feed(&bytes[split..]).unwrap();
+                value = parser.next_value().unwrap();
+            }
+            assert_eq!(
+                value,
+                Incremental::Ready(expected.clone()),
+                "split at {}",
+                split
+            );
+            parser.finish().unwrap();
+            assert_eq!(parser.next_value(), Ok(Incremental::End));
+        }
+
+        let mut parser = PushParser::with_options(options);
+        let mut value = None;
+        for byte in bytes {
+            parser.feed(&[*byte]).unwrap();
+            if let Incremental::Ready(json) = parser.next_value().unwrap() {
+                value = Some(json);
+            }
+        }
+        assert_eq!(value, Some(expected));
+    }
+
+    #[test]
+    pub fn should_report_errors_with_positions_in_the_whole_input() {
+        let mut parser = PushParser::new();
+        parser.feed(b"[1,\n").unwrap();
+        parser.feed(b" }").unwrap();
+        parser.next_event().unwrap();
+        parser.next_event().unwrap();
+        assert_eq!(
+            parser.next_event().unwrap_err().position(),
+            Position {
+                offset: 5,
+                line: 2,
+                column: 2
+            }
+        );
+        assert_eq!(parser.next_event(), Ok(Incremental::End));
+
+        let mut parser = PushParser::new();
+        parser.feed(b"[1, ").unwrap();
+        parser.finish().unwrap();
+        parser.next_event().unwrap();
+        parser.next_event().unwrap();
+        assert_eq!(
+            parser.next_event(),
+            Err(JsonError::UnexpectedEof {
+                position: Position {
+                    offset: 4,
+                    line: 1,
+                    column: 5
+                }
+            })
+        );
+
+        let mut parser = PushParser::new();
+        parser.feed(b"1 2").unwrap();
+        parser.finish().unwrap();
+        assert_eq!(parser.next_value(), Ok(Incremental::Ready(Json::from(1))));
+        assert!(matches!(
+            parser.next_value(),
+            Err(JsonError::TrailingCharacters { .. })
+        ));
+    }
+
+    #[test]
+    pub fn should_reject_invalid_utf8() {
+        let mut parser = PushParser::new();
+        parser.feed("[\"é".as_bytes()).unwrap();
+        assert_eq!(
+            parser.feed(&[b'x', 0xff]),
+            Err(JsonError::InvalidUtf8 {
+                position: Position {
+                    offset: 5,
+                    line: 1,
+                    column: 5
+                }
+            })
+        );
+
+        let mut parser = PushParser::new();
+        parser.feed(&"\"é\"".as_bytes()[..2]).unwrap();
+        assert_eq!(
+            parser.finish(),
+            Err(JsonError::InvalidUtf8 {
+                position: Position {
+                    offset: 1,
+                    line: 1,
+                    column: 2
+                }
+            })
+        );
+    }
+
+    // 按照`chunk`字节一块提供输入, 返回读到的顶层值.
+    fn parse_in_chunks(text: &str, options: ParserOptions, chunk: usize) -> json::Result<Json> {
+        let mut parser = PushParser::with_options(options);
+        let mut value = None;
+        for bytes in text.as_bytes().chunks(chunk) {
+            parser.feed(bytes)?;
+            if let Incremental::Ready(json) = parser.next_value()? {
+                value = Some(json);
+            }
+        }
+        parser.finish()?;
+        loop {
+            match parser.next_value()? {
+                Incremental::Ready(json) => value = Some(json),
+                Incremental::NeedMoreInput => unreachable!(),
+                Incremental::End => return Ok(value.expect("a value")),
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_parse_input_split_inside_line_comments() {
+        let options = ParserOptions::jsonc();
+        let mut parser = PushParser::with_options(options);
+        parser.feed(b"// hello").unwrap();
+        assert_eq!(parser.next_value(), Ok(Incremental::NeedMoreInput));
+        parser.feed(b" world\n[1]").unwrap();
+        assert_eq!(
+            parser.next_value(),
+            Ok(Incremental::Ready(Json::from(vec![Json::from(1)])))
+        );
+
+        let mut parser = PushParser::with_options(options);
+        parser.feed(b"[1, // a").unwrap();
+        parser.feed(b"b\n 2]").unwrap();
+        assert_eq!(
+            parser.next_value(),
+            Ok(Incremental::Ready(Json::from(vec![
+                Json::from(1),
+                Json::from(2)
+            ])))
+        );
+
+        let text =
+            "// 头部\n{a: [1, // 一\n 'x//y', /* b */ 2e1 // c\n], // d\n \"k\": null}\n// 尾部";
+        for &options in &[
+            ParserOptions::json5(),
+            ParserOptions::json5().emit_comments(true),
+        ] {
+            let expected = json::parse_with(text, options).unwrap();
+            for chunk in 1..text.len() {
+                assert_eq!(
+                    parse_in_chunks(text, options, chunk),
+                    Ok(expected.clone()),
+                    "chunk {}",
+                    chunk
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_end_line_comments_at_any_line_terminator_in_chunks() {
+        let text = "// a\r[1, // b\u{2028}2, // c\u{2029}3] // d\r";
+        let expected = Json::from(vec![Json::from(1), Json::from(2), Json::from(3)]);
+        for &options in &[
+            ParserOptions::json5(),
+            ParserOptions::json5().emit_comments(true),
+        ] {
+            for chunk in 1..text.len() {
+                assert_eq!(
+                    parse_in_chunks(text, options, chunk),
+                    Ok(expected.clone()),
+                    "chunk {}",
+                    chunk
+                );
+            }
+        }
+    }
+
+    #[test]
+    pub fn should_parse_long_strings_in_small_chunks() {
+        let content = "a\\\"é".repeat(1 << 18);
+        let text = format!("[\"{}\", 0.{}]", content, "1".repeat(100_000));
+        assert_eq!(
+            parse_in_chunks(&text, ParserOptions::default(), 4096),
+            json::parse(&text)
+        );
+    }
+}
@@ -1,6 +1,10 @@
 #[cfg(test)]
 pub mod tokenizer_tests {
-    use json::token::Token;
+    use json::error::JsonError;
+    use json::number::Number;
+    use json::parser::ParserOptions;
+    use json::position::{Position, Span};
+    use json::token::{SpannedToken, Token};
     use json::tokenizer::Tokenizer;
 
     #[test]
@@ -12,8 +16,8 @@ pub mod tokenizer_tests {
     pub fn should_parse_brackets() {
         let mut tokenizer = Tokenizer::new("{}");
 
-        assert_eq!(tokenizer.next(), Some(Token::BraceOn));
-        assert_eq!(tokenizer.next(), Some(Token::BraceOff));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BraceOn)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BraceOff)));
         assert_eq!(tokenizer.next(), None);
     }
 
@@ -21,8 +25,8 @@ pub mod tokenizer_tests {
     pub fn should_parse_braces() {
         let mut tokenizer = Tokenizer::new("[]");
 
-        assert_eq!(tokenizer.next(), Some(Token::BracketOn));
-        assert_eq!(tokenizer.next(), Some(Token::BracketOff));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BracketOn)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BracketOff)));
         assert_eq!(tokenizer.next(), None);
     }
 
@@ -30,42 +34,51 @@ pub mod tokenizer_tests {
     pub fn should_parse_null() {
         let mut tokenizer = Tokenizer::new("null");
 
-        assert_eq!(tokenizer.next(), Some(Token::Null));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Null)));
         assert_eq!(tokenizer.next(), None);
     }
 
     #[test]
     pub fn should_parse_boolean_value() {
         let mut tokenizer = Tokenizer::new("true");
-        assert_eq!(tokenizer.next(), Some(Token::Boolean(true)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Boolean(true))));
         assert_eq!(tokenizer.next(), None);
 
         let mut tokenizer = Tokenizer::new("false");
-        assert_eq!(tokenizer.next(), Some(Token::Boolean(false)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Boolean(false))));
         assert_eq!(tokenizer.next(), None);
     }
 
     #[test]
     pub fn should_parse_number_value() {
         let mut tokenizer = Tokenizer::new("123");
-        assert_eq!(tokenizer.next(), Some(Token::Number(123.)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Number::from(123)))));
 
         let mut tokenizer = Tokenizer::new("1.23");
-        assert_eq!(tokenizer.next(), Some(Token::Number(1.23)));
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number(Number::from(1.23))))
+        );
 
         let mut tokenizer = Tokenizer::new("-1.23");
-        assert_eq!(tokenizer.next(), Some(Token::Number(-1.23)));
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number(Number::from(-1.23))))
+        );
     }
 
     #[test]
     pub fn should_parse_object() {
         let mut tokenizer = Tokenizer::new(r#"{ "key": "value" }"#);
 
-        assert_eq!(tokenizer.next(), Some(Token::BraceOn));
-        assert_eq!(tokenizer.next(), Some(Token::String("key".to_owned())));
-        assert_eq!(tokenizer.next(), Some(Token::Colon));
-        assert_eq!(tokenizer.next(), Some(Token::String("value".to_owned())));
-        assert_eq!(tokenizer.next(), Some(Token::BraceOff));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BraceOn)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::String("key".to_owned()))));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Colon)));
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::String("value".to_owned())))
+        );
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BraceOff)));
         assert_eq!(tokenizer.next(), None);
     }
 
@@ -73,17 +86,331 @@ pub mod tokenizer_tests {
     pub fn should_parse_array() {
         let mut tokenizer = Tokenizer::new(r#"{ "key": [1, 2, "v1"] }"#);
 
-        assert_eq!(tokenizer.next(), Some(Token::BraceOn));
-        assert_eq!(tokenizer.next(), Some(Token::String("key".to_owned())));
-        assert_eq!(tokenizer.next(), Some(Token::Colon));
-        assert_eq!(tokenizer.next(), Some(Token::BracketOn));
-        assert_eq!(tokenizer.next(), Some(Token::Number(1.)));
-        assert_eq!(tokenizer.next(), Some(Token::Comma));
-        assert_eq!(tokenizer.next(), Some(Token::Number(2.)));
-        assert_eq!(tokenizer.next(), Some(Token::Comma));
-        assert_eq!(tokenizer.next(), Some(Token::String("v1".to_owned())));
-        assert_eq!(tokenizer.next(), Some(Token::BracketOff));
-        assert_eq!(tokenizer.next(), Some(Token::BraceOff));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BraceOn)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::String("key".to_owned()))));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Colon)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BracketOn)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Number::from(1)))));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Comma)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Number::from(2)))));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Comma)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::String("v1".to_owned()))));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BracketOff)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BraceOff)));
         assert_eq!(tokenizer.next(), None);
     }
+
+    #[test]
+    pub fn should_parse_number_with_exponent() {
+        let mut tokenizer = Tokenizer::new("1e10 2.5E-3 -0.5e+2 0");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number(Number::from(1e10))))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number(Number::from(2.5e-3))))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number(Number::from(-50.0))))
+        );
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Number::from(0)))));
+        assert_eq!(tokenizer.next(), None);
+    }
+
+    #[test]
+    pub fn should_stop_number_at_second_minus() {
+        let mut tokenizer = Tokenizer::new("1-2");
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Number::from(1)))));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Number::from(-2)))));
+        assert_eq!(tokenizer.next(), None);
+    }
+
+    #[test]
+    pub fn should_report_invalid_number() {
+        for (source, number) in &[
+            (" 01", "01"),
+            (" -", "-"),
+            (" -a", "-"),
+            (" 1.", "1."),
+            (" 1.e5", "1."),
+            (" 1e", "1e"),
+            (" 1e+", "1e+"),
+        ] {
+            let mut tokenizer = Tokenizer::new(source);
+            assert_eq!(
+                tokenizer.next(),
+                Some(Err(JsonError::InvalidNumber {
+                    number: number.to_string(),
+                    position: Position::new(1, 1, 2),
+                }))
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_report_unexpected_character() {
+        let mut tokenizer = Tokenizer::new("\n  @");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Err(JsonError::UnexpectedCharacter {
+                ch: '@',
+                position: Position::new(3, 2, 3),
+            }))
+        );
+    }
+
+    #[test]
+    pub fn should_track_token_spans() {
+        let mut tokenizer = Tokenizer::new("{\n  \"k\u{e9}y\": true\n}");
+
+        assert_eq!(
+            tokenizer.next_spanned(),
+            Some(Ok(SpannedToken::new(
+                Token::BraceOn,
+                Span::new(Position::new(0, 1, 1), Position::new(1, 1, 2))
+            )))
+        );
+        assert_eq!(
+            tokenizer.next_spanned(),
+            Some(Ok(SpannedToken::new(
+                Token::String("k\u{e9}y".to_owned()),
+                Span::new(Position::new(4, 2, 3), Position::new(10, 2, 8))
+            )))
+        );
+        assert_eq!(
+            tokenizer.next_spanned(),
+            Some(Ok(SpannedToken::new(
+                Token::Colon,
+                Span::new(Position::new(10, 2, 8), Position::new(11, 2, 9))
+            )))
+        );
+        assert_eq!(
+            tokenizer.next_spanned(),
+            Some(Ok(SpannedToken::new(
+                Token::Boolean(true),
+                Span::new(Position::new(12, 2, 10), Position::new(16, 2, 14))
+            )))
+        );
+        assert_eq!(
+            tokenizer.next_spanned(),
+            Some(Ok(SpannedToken::new(
+                Token::BraceOff,
+                Span::new(Position::new(17, 3, 1), Position::new(18, 3, 2))
+            )))
+        );
+        assert_eq!(tokenizer.next_spanned(), None);
+    }
+
+    #[test]
+    pub fn should_decode_escapes() {
+        let mut tokenizer = Tokenizer::new(r#""\"\\\/\b\f\n\r\t""#);
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::String("\"\\/\u{8}\u{c}\n\r\t".to_owned())))
+        );
+
+        let mut tokenizer = Tokenizer::new(r#""caf\u00e9 \uD83D\uDE00""#);
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::String("caf\u{e9} \u{1f600}".to_owned())))
+        );
+    }
+
+    #[test]
+    pub fn should_report_invalid_escapes() {
+        let mut tokenizer = Tokenizer::new(r#""ab\x""#);
+        assert_eq!(
+            tokenizer.next(),
+            Some(Err(JsonError::InvalidEscape {
+                ch: 'x',
+                position: Position::new(3, 1, 4),
+            }))
+        );
+
+        let mut tokenizer = Tokenizer::new(r#""\u12G4""#);
+        assert_eq!(
+            tokenizer.next(),
+            Some(Err(JsonError::InvalidUnicodeEscape {
+                position: Position::new(1, 1, 2),
+            }))
+        );
+    }
+
+    #[test]
+    pub fn should_report_lone_surrogates() {
+        for source in &[r#""\uD83D""#, r#""\uD83Dx""#, r#""\uD83DA""#, r#""\uDE00""#] {
+            let mut tokenizer = Tokenizer::new(source);
+            assert_eq!(
+                tokenizer.next(),
+                Some(Err(JsonError::LoneSurrogate {
+                    position: Position::new(1, 1, 2),
+                }))
+            );
+        }
+    }
+
+    #[test]
+    pub fn should_report_raw_control_characters() {
+        let mut tokenizer = Tokenizer::new("\"a\tb\"");
+        assert_eq!(
+            tokenizer.next(),
+            Some(Err(JsonError::ControlCharacter {
+                ch: '\t',
+                position: Position::new(2, 1, 3),
+            }))
+        );
+    }
+
+    #[test]
+    pub fn should_skip_comments() {
+        let options = ParserOptions::new().allow_comments(true);
+        let mut tokenizer = Tokenizer::with_options("// a\n[/* b */1 /**/]// c", options);
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BracketOn)));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Number::from(1)))));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BracketOff)));
+        assert_eq!(tokenizer.next(), None);
+
+        let mut tokenizer = Tokenizer::with_options("[\n  /* a **", options);
+        assert_eq!(tokenizer.next(), Some(Ok(Token::BracketOn)));
+        assert_eq!(
+            tokenizer.next(),
+            Some(Err(JsonError::UnterminatedComment {
+                position: Position::new(4, 2, 3),
+            }))
+        );
+
+        let mut tokenizer = Tokenizer::with_options("/x", options);
+        assert_eq!(
+            tokenizer.next(),
+            Some(Err(JsonError::UnexpectedCharacter {
+                ch: '/',
+                position: Position::new(0, 1, 1),
+            }))
+        );
+    }
+
+    #[test]
+    pub fn should_read_lenient_numbers() {
+        let options = ParserOptions::lenient();
+        let mut tokenizer = Tokenizer::with_options("+1 0x1f -0XFF +Infinity 0x", options);
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Number::from(1)))));
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Number::from(31)))));
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number(Number::from(-255))))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number(Number::from(f64::INFINITY))))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Err(JsonError::InvalidNumber {
+                number: "0x".to_owned(),
+                position: Position::new(24, 1, 25),
+            }))
+        );
+
+        let options = ParserOptions::lenient().arbitrary_precision(true);
+        let mut tokenizer = Tokenizer::with_options("+1.50 0x10", options);
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number("1.50".parse().unwrap())))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number("16".parse().unwrap())))
+        );
+    }
+
+    #[test]
+    pub fn should_read_single_quoted_strings_and_identifiers() {
+        let options = ParserOptions::lenient();
+        let mut tokenizer = Tokenizer::with_options(r#"'a"\'' _kéy$2"#, options);
+        assert_eq!(tokenizer.next(), Some(Ok(Token::String("a\"'".to_owned()))));
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Identifier("_kéy$2".to_owned())))
+        );
+
+        let mut tokenizer = Tokenizer::new(r#""\'""#);
+        assert_eq!(
+            tokenizer.next(),
+            Some(Err(JsonError::InvalidEscape {
+                ch: '\'',
+                position: Position::new(1, 1, 2),
+            }))
+        );
+    }
+
+    #[test]
+    pub fn should_emit_comments_as_trivia() {
+        let options = ParserOptions::new()
+            .allow_comments(true)
+            .emit_comments(true);
+        let mut tokenizer = Tokenizer::with_options("1 // one\n/* two\n **/", options);
+
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Number::from(1)))));
+        assert_eq!(
+            tokenizer.next_spanned(),
+            Some(Ok(SpannedToken::new(
+                Token::LineComment(" one".to_owned()),
+                Span::new(Position::new(2, 1, 3), Position::new(8, 1, 9))
+            )))
+        );
+        assert_eq!(
+            tokenizer.next_spanned(),
+            Some(Ok(SpannedToken::new(
+                Token::BlockComment(" two\n *".to_owned()),
+                Span::new(Position::new(9, 2, 1), Position::new(20, 3, 5))
+            )))
+        );
+        assert_eq!(tokenizer.next(), None);
+    }
+
+    #[test]
+    pub fn should_read_json5_numbers_and_escapes() {
+        let options = ParserOptions::json5();
+        let mut tokenizer = Tokenizer::with_options(".5 -5. +.5e1 1.e2 -.", options);
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Number::from(0.5)))));
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number(Number::from(-5.0))))
+        );
+        assert_eq!(tokenizer.next(), Some(Ok(Token::Number(Number::from(5.0)))));
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number(Number::from(100.0))))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Err(JsonError::InvalidNumber {
+                number: "-.".to_owned(),
+                position: Position::new(18, 1, 19),
+            }))
+        );
+
+        let options = ParserOptions::json5().arbitrary_precision(true);
+        let mut tokenizer = Tokenizer::with_options("-.50 7.", options);
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number("-0.50".parse().unwrap())))
+        );
+        assert_eq!(
+            tokenizer.next(),
+            Some(Ok(Token::Number("7.0".parse().unwrap())))
+        );
+
+        let mut tokenizer = Tokenizer::with_options("'a\\\r\nb\\\u{2028}c' '\\xZ1'", options);
+        assert_eq!(tokenizer.next(), Some(Ok(Token::String("abc".to_owned()))));
+        assert_eq!(
+            tokenizer.next(),
+            Some(Err(JsonError::InvalidEscape {
+                ch: 'x',
+                position: Position::new(14, 2, 8),
+            }))
+        );
+    }
 }
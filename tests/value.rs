@@ -0,0 +1,69 @@
+#[cfg(test)]
+pub mod value_tests {
+    use json::hash_map;
+    use json::map::Map;
+    use json::parser::ParserOptions;
+    use json::value::Json;
+
+    fn nested(depth: usize, leaf: &str) -> Json {
+        let source = format!(
+            r#"{}{{"k": [{}, {{}}]}}{}"#,
+            "[".repeat(depth),
+            leaf,
+            "]".repeat(depth)
+        );
+        json::parse_with(&source, ParserOptions::new().max_depth(usize::MAX)).unwrap()
+    }
+
+    #[test]
+    pub fn should_clone_and_compare_deeply_nested_values_without_recursion() {
+        let depth = 300_000;
+        let json = nested(depth, "1");
+
+        let copy = json.clone();
+        assert!(json == copy);
+        assert!(json != nested(depth, "2"));
+        assert!(json != nested(depth - 1, "1"));
+        assert_eq!(json::stringify(copy).len(), 2 * depth + 12);
+    }
+
+    #[test]
+    pub fn should_compare_objects_regardless_of_key_order() {
+        let mut a = Map::new();
+        a.insert("x".to_owned(), Json::from(vec![Json::from(1), Json::Null]));
+        a.insert("y".to_owned(), Json::from("s"));
+        let mut b = Map::new();
+        b.insert("y".to_owned(), Json::from("s"));
+        b.insert("x".to_owned(), Json::from(vec![Json::from(1), Json::Null]));
+
+        assert_eq!(Json::from(a.clone()), Json::from(b.clone()));
+        assert_eq!(Json::from(a.clone()).clone(), Json::from(a.clone()));
+
+        b.insert("z".to_owned(), Json::Null);
+        assert_ne!(Json::from(a.clone()), Json::from(b));
+        assert_ne!(
+            Json::from(a),
+            Json::from(hash_map! {
+                "x".to_owned() => Json::from(vec![Json::from(1), Json::from(false)]),
+                "y".to_owned() => Json::from("s")
+            })
+        );
+        assert_ne!(Json::from(1), Json::from(1.0));
+    }
+
+    #[test]
+    pub fn should_take_ownership_of_parsed_values() {
+        let json = json::parse(r#"[{"k": "v"}, 1]"#).unwrap();
+        let mut array = json.into_array().unwrap();
+        assert_eq!(array.len(), 2);
+
+        let mut object = array[0].take().into_object().unwrap();
+        assert_eq!(array[0], Json::Null);
+        let value = object.remove("k").unwrap().into_string();
+        assert_eq!(value, Some("v".to_owned()));
+        assert!(object.is_empty());
+
+        assert_eq!(array.pop().unwrap().into_array(), None);
+        assert_eq!(std::mem::take(&mut array[0]), Json::default());
+    }
+}